@@ -0,0 +1,428 @@
+//! One entry point for configuring instrumented connections
+//!
+//! [`InstrumentedGraph::builder`] returns an [`InstrumentedGraphBuilder`], which collects
+//! metrics, telemetry and naming settings up front and produces an
+//! [`InstrumentedGraphConfig`] that can connect any number of graphs with the same setup. It
+//! is equivalent to calling the `with_*` methods of [`InstrumentedGraph`] after
+//! [`InstrumentedGraph::connect`].
+//!
+//! Which tracer receives the spans is decided by the `tracing` subscriber the application
+//! installs (typically a `tracing-opentelemetry` layer), not by this builder.
+//! [`with_tracing(false)`](InstrumentedGraphBuilder::with_tracing) turns span creation off
+//! in code.
+
+use crate::graph::InstrumentedGraph;
+#[cfg(feature = "metrics")]
+use crate::metrics::{MetricsBuilder, Neo4jMetrics};
+use crate::redact::Redactor;
+use crate::retry::RetryPolicy;
+use crate::telemetry::TelemetryConfig;
+use neo4rs::Graph;
+#[cfg(feature = "metrics")]
+use opentelemetry::metrics::Meter;
+use opentelemetry::{Key, KeyValue, Value};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Builder for an [`InstrumentedGraphConfig`]
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use otel_instrumentation_neo4jrs::InstrumentedGraph;
+///
+/// # async fn example() -> Result<(), neo4rs::Error> {
+/// let graph = InstrumentedGraph::builder()
+///     .with_service_name("orders-graph")
+///     .with_statement_recording(false)
+///     .build()
+///     .connect("bolt://localhost:7687", "neo4j", "password")
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct InstrumentedGraphBuilder {
+    #[cfg(feature = "metrics")]
+    metrics: MetricsBuilder,
+    default_database: Option<String>,
+    config: TelemetryConfig,
+}
+
+impl InstrumentedGraphBuilder {
+    /// Create a builder with metrics disabled and default telemetry settings
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record metrics with `meter`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use opentelemetry::global;
+    /// use otel_instrumentation_neo4jrs::InstrumentedGraph;
+    ///
+    /// let config = InstrumentedGraph::builder()
+    ///     .with_meter(global::meter("neo4j"))
+    ///     .build();
+    /// assert!(config.metrics().is_some());
+    /// ```
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub fn with_meter(mut self, meter: Meter) -> Self {
+        self.metrics = self.metrics.with_meter(meter);
+        self
+    }
+
+    /// Record `name` as `peer.service` on every operation span and metric data point
+    ///
+    /// `name` identifies the Neo4j deployment the graph talks to, e.g. `orders-graph`. It is
+    /// not the `service.name` of the application, which belongs on the tracer's and meter's
+    /// `Resource`.
+    #[must_use]
+    pub fn with_service_name(mut self, name: &str) -> Self {
+        self.config = self.config.with_service_name(name);
+        #[cfg(feature = "metrics")]
+        {
+            self.metrics = self.metrics.with_attribute("peer.service", name.to_owned());
+        }
+        self
+    }
+
+    /// Record statement text and parameters of traced queries; enabled by default
+    #[must_use]
+    pub fn with_statement_recording(mut self, enabled: bool) -> Self {
+        self.config = self.config.with_statement_recording(enabled);
+        self
+    }
+
+    /// Record `db.query.text` only for failed queries, see
+    /// [`InstrumentedGraph::with_statement_recording_on_error`]
+    #[must_use]
+    pub fn with_statement_recording_on_error(mut self, enabled: bool) -> Self {
+        self.config = self.config.with_statement_recording_on_error(enabled);
+        self
+    }
+
+    /// Log successful operations at `INFO`, see [`InstrumentedGraph::with_success_logging`]
+    #[must_use]
+    pub fn with_success_logging(mut self, enabled: bool) -> Self {
+        self.config = self.config.with_success_logging(enabled);
+        self
+    }
+
+    /// Add `key = value` to every operation span, transaction span and metric data point
+    ///
+    /// Use it for deployment-wide tags such as `tenant.id` or `deployment.environment`.
+    /// Every distinct value starts a new time series for each metric instrument, so keep to
+    /// low-cardinality keys with a small, fixed set of values; per-request or per-user values
+    /// belong in the application's own spans instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use otel_instrumentation_neo4jrs::InstrumentedGraph;
+    ///
+    /// let config = InstrumentedGraph::builder()
+    ///     .with_attribute("tenant.id", "acme")
+    ///     .with_attribute("deployment.environment", "production")
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn with_attribute(mut self, key: impl Into<Key>, value: impl Into<Value>) -> Self {
+        let attribute = KeyValue::new(key, value);
+        #[cfg(feature = "metrics")]
+        {
+            self.metrics = self
+                .metrics
+                .with_attribute(attribute.key.clone(), attribute.value.clone());
+        }
+        self.config = self.config.with_attribute(attribute.key, attribute.value);
+        self
+    }
+
+    /// Flag database calls taking longer than `threshold`
+    ///
+    /// Slow calls emit a `neo4j.slow_query` warning event on their span and, with metrics
+    /// enabled, increment the `neo4j.queries.slow` counter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use otel_instrumentation_neo4jrs::InstrumentedGraph;
+    /// use std::time::Duration;
+    ///
+    /// let config = InstrumentedGraph::builder()
+    ///     .with_slow_query_threshold(Duration::from_millis(500))
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn with_slow_query_threshold(mut self, threshold: Duration) -> Self {
+        #[cfg(feature = "metrics")]
+        {
+            self.metrics = self.metrics.with_slow_query_threshold(threshold);
+        }
+        self.config = self.config.with_slow_query_threshold(threshold);
+        self
+    }
+
+    /// Name exported spans `<prefix>.<operation>`, see [`InstrumentedGraph::with_span_prefix`]
+    #[must_use]
+    pub fn with_span_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.config = self.config.with_span_prefix(prefix);
+        self
+    }
+
+    /// Record only the listed parsed operations by name, see
+    /// [`InstrumentedGraph::with_operation_allowlist`]
+    #[must_use]
+    pub fn with_operation_allowlist(mut self, operations: HashSet<String>) -> Self {
+        self.config = self.config.with_operation_allowlist(operations);
+        self
+    }
+
+    /// Record traced query parameters filtered through `redactor`, see
+    /// [`InstrumentedGraph::with_redactor`]
+    #[must_use]
+    pub fn with_redactor(mut self, redactor: Box<dyn Redactor>) -> Self {
+        self.config = self.config.with_redactor(redactor);
+        self
+    }
+
+    /// Create spans for operations at all, see [`InstrumentedGraph::with_tracing`]
+    #[must_use]
+    pub fn with_tracing(mut self, enabled: bool) -> Self {
+        self.config = self.config.with_tracing(enabled);
+        self
+    }
+
+    /// Name the default database in telemetry, see
+    /// [`InstrumentedGraph::with_default_database`]
+    #[must_use]
+    pub fn with_default_database(mut self, db: impl Into<String>) -> Self {
+        self.default_database = Some(db.into());
+        self
+    }
+
+    /// Start from `config` for every other telemetry setting
+    ///
+    /// Settings applied through this builder before the call are replaced.
+    #[must_use]
+    pub fn with_telemetry_config(mut self, config: TelemetryConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Finish configuration
+    #[must_use]
+    pub fn build(self) -> InstrumentedGraphConfig {
+        InstrumentedGraphConfig {
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics.build(),
+            default_database: self.default_database,
+            config: Arc::new(self.config),
+        }
+    }
+}
+
+/// Settings produced by [`InstrumentedGraphBuilder`], ready to connect graphs
+///
+/// Graphs connected from the same config share one set of metric instruments.
+#[derive(Clone, Debug)]
+pub struct InstrumentedGraphConfig {
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Neo4jMetrics>>,
+    default_database: Option<String>,
+    config: Arc<TelemetryConfig>,
+}
+
+impl InstrumentedGraphConfig {
+    /// Connect like [`InstrumentedGraph::connect`] and apply these settings
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection to Neo4j fails
+    pub async fn connect(
+        &self,
+        uri: &str,
+        user: &str,
+        password: &str,
+    ) -> Result<InstrumentedGraph, neo4rs::Error> {
+        let graph =
+            InstrumentedGraph::connect_with_config(uri, user, password, None, self.config.clone())
+                .await?;
+        Ok(self.apply(graph))
+    }
+
+    /// Connect like [`InstrumentedGraph::connect_with_retry`] and apply these settings
+    ///
+    /// The connect spans are named by this config's span prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error once the policy's attempts are exhausted, or the first
+    /// non-connection error
+    pub async fn connect_with_retry(
+        &self,
+        uri: &str,
+        user: &str,
+        password: &str,
+        policy: RetryPolicy,
+    ) -> Result<InstrumentedGraph, neo4rs::Error> {
+        let graph = InstrumentedGraph::connect_with_retry_and_config(
+            uri,
+            user,
+            password,
+            policy,
+            self.config.clone(),
+        )
+        .await?;
+        Ok(self.apply(graph))
+    }
+
+    /// Wrap an existing `graph` like [`InstrumentedGraph::new`] and apply these settings
+    ///
+    /// Records `localhost:7687` as the server endpoint; use
+    /// [`wrap_with_endpoint`](Self::wrap_with_endpoint) if the graph connects elsewhere.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use neo4rs::Graph;
+    /// # use otel_instrumentation_neo4jrs::InstrumentedGraph;
+    /// # async fn example() -> Result<(), neo4rs::Error> {
+    /// let graph = Graph::new("bolt://localhost:7687", "neo4j", "password").await?;
+    /// let graph = InstrumentedGraph::builder()
+    ///     .with_service_name("orders-graph")
+    ///     .build()
+    ///     .wrap(graph);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn wrap(&self, graph: Graph) -> InstrumentedGraph {
+        self.apply(InstrumentedGraph::new(graph))
+    }
+
+    /// Wrap an existing `graph` connected to `address:port` like
+    /// [`InstrumentedGraph::new_with_endpoint`] and apply these settings
+    #[must_use]
+    pub fn wrap_with_endpoint(
+        &self,
+        graph: Graph,
+        address: impl Into<String>,
+        port: u16,
+    ) -> InstrumentedGraph {
+        self.apply(InstrumentedGraph::new_with_endpoint(graph, address, port))
+    }
+
+    /// The metrics graphs connected from this config record to, if enabled
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub fn metrics(&self) -> Option<&Arc<Neo4jMetrics>> {
+        self.metrics.as_ref()
+    }
+
+    fn apply(&self, graph: InstrumentedGraph) -> InstrumentedGraph {
+        let mut graph = graph.with_telemetry_config(self.config.clone());
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            graph = graph.with_metrics_instance(metrics.clone());
+        }
+        if let Some(db) = &self.default_database {
+            graph = graph.with_default_database(db.clone());
+        }
+        graph
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use super::*;
+    use opentelemetry::metrics::MeterProvider as _;
+    use opentelemetry_sdk::metrics::SdkMeterProvider;
+
+    #[tokio::test]
+    async fn test_config_wires_graph() {
+        let provider = SdkMeterProvider::default();
+        let config = InstrumentedGraph::builder()
+            .with_meter(provider.meter("test"))
+            .with_service_name("orders-graph")
+            .with_statement_recording(false)
+            .with_default_database("orders")
+            .build();
+
+        // neo4rs connects lazily, so no server is needed
+        let graph = config
+            .connect("bolt://127.0.0.1:1", "neo4j", "password")
+            .await
+            .unwrap();
+
+        assert!(graph.is_metrics_enabled());
+        assert!(config.metrics().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_config_wraps_existing_graph() {
+        use crate::test_support::{attribute, CapturedSpans};
+
+        let capture = CapturedSpans::install();
+        let provider = SdkMeterProvider::default();
+        let config = InstrumentedGraph::builder()
+            .with_meter(provider.meter("test"))
+            .with_redactor(Box::new(crate::redact::MaskAllRedactor))
+            .with_default_database("orders")
+            .build();
+        let graph = Graph::new("bolt://127.0.0.1:1", "neo4j", "password")
+            .await
+            .unwrap();
+
+        let graph = config
+            .wrap_with_endpoint(graph, "db.internal", 7688)
+            .with_middleware(Arc::new(crate::middleware::Reject));
+        assert!(graph.is_metrics_enabled());
+
+        let query = crate::query::TracedQuery::new("RETURN $id").param("id", 42);
+        assert!(graph.run_traced_query(query).await.is_err());
+
+        let spans = capture.spans();
+        let span = spans.first().expect("Should have an operation span");
+        assert_eq!(
+            attribute(span, "server.address"),
+            Some(&Value::from("db.internal"))
+        );
+        assert_eq!(
+            attribute(span, "db.namespace"),
+            Some(&Value::from("orders"))
+        );
+        assert!(attribute(span, "db.query.parameters").is_some());
+    }
+
+    #[test]
+    fn test_service_name_labels_metrics() {
+        use crate::test_support::{sum_points, CapturedMetrics};
+        use opentelemetry::KeyValue;
+
+        let captured = CapturedMetrics::install_with(|meter| {
+            let config = InstrumentedGraph::builder()
+                .with_meter(meter.clone())
+                .with_service_name("orders-graph")
+                .build();
+            (**config.metrics().expect("metrics are enabled")).clone()
+        });
+        captured.metrics.record_transaction_start("orders");
+
+        let points = sum_points(&captured, "neo4j.transactions.total");
+        assert!(points[0]
+            .0
+            .contains(&KeyValue::new("peer.service", "orders-graph")));
+    }
+
+    #[test]
+    fn test_metrics_disabled_without_meter() {
+        assert!(InstrumentedGraphBuilder::new().build().metrics().is_none());
+    }
+}
@@ -0,0 +1,410 @@
+//! Error classification for telemetry
+//!
+//! Neo4j status codes follow the `Neo.<Classification>.<Category>.<Title>` layout, e.g.
+//! `Neo.ClientError.Statement.SyntaxError`. The helpers here turn an error into
+//! low-cardinality labels suitable for metric attributes: a fine-grained category, the
+//! coarse classification tier that separates client mistakes from server faults, and a
+//! semantic `error.type` value for spans and dashboards. [`is_retryable`] tells transient
+//! blips apart from failures that will happen again.
+//!
+//! Every fallible database method returns the `neo4rs::Error` the driver produced,
+//! unchanged, so callers match on it exactly as they would without the wrapper. The context
+//! an error type would carry, such as the operation, database and server address, is
+//! recorded on the failed operation's span and log event instead. Only
+//! [`connect_from_env`](crate::InstrumentedGraph::connect_from_env) wraps the driver error,
+//! in an [`EnvConfigError`](crate::EnvConfigError) that can also name a bad variable.
+
+/// Classification tier for errors raised by the driver rather than the server
+pub const DRIVER_ERROR: &str = "DriverError";
+
+/// Fine-grained category of an error
+///
+/// For server errors this is the category segment of the Neo4j status code (`Statement`,
+/// `Transaction`, `Security`, ...). Driver errors map to a short description of the failure.
+#[must_use]
+pub fn error_category(err: &neo4rs::Error) -> &str {
+    match err {
+        neo4rs::Error::Neo4j(e) => code_category(e.code()),
+        neo4rs::Error::IOError { .. } | neo4rs::Error::ConnectionError => "connection",
+        neo4rs::Error::AuthenticationError(_) => "authentication",
+        neo4rs::Error::UrlParseError(_)
+        | neo4rs::Error::UnsupportedScheme(_)
+        | neo4rs::Error::InvalidDnsName(_)
+        | neo4rs::Error::InvalidConfig => "configuration",
+        neo4rs::Error::StringTooLong
+        | neo4rs::Error::MapTooBig
+        | neo4rs::Error::BytesTooBig
+        | neo4rs::Error::ListTooLong => "serialization",
+        neo4rs::Error::ConversionError | neo4rs::Error::DeserializationError(_) => {
+            "deserialization"
+        }
+        // Unexpected messages, unknown types and version mismatches
+        _ => "protocol",
+    }
+}
+
+/// Classification tier of an error
+///
+/// Returns `ClientError`, `TransientError` or `DatabaseError` for errors reported by the
+/// server, and [`DRIVER_ERROR`] for failures that never reached it.
+#[must_use]
+pub fn error_class(err: &neo4rs::Error) -> &'static str {
+    match err {
+        neo4rs::Error::Neo4j(e) => code_class(e.code()),
+        _ => DRIVER_ERROR,
+    }
+}
+
+/// Semantic `error.type` value for an error
+///
+/// Maps the failures worth telling apart on a dashboard to stable `snake_case` values:
+/// `connection_refused`, `timeout`, `syntax_error`, `constraint_violation`, `transient`,
+/// `authentication`, `forbidden`, `database_not_found` and so on. Anything without a more
+/// specific value falls back to `client_error`, `database_error` or a driver-side bucket such
+/// as `io` or `protocol`.
+///
+/// # Example
+///
+/// ```rust
+/// use otel_instrumentation_neo4jrs::error::classify_error;
+///
+/// let err = std::io::Error::from(std::io::ErrorKind::ConnectionRefused).into();
+/// assert_eq!(classify_error(&err), "connection_refused");
+/// ```
+#[must_use]
+pub fn classify_error(err: &neo4rs::Error) -> &'static str {
+    match err {
+        neo4rs::Error::Neo4j(e) => classify_code(e.code()),
+        neo4rs::Error::IOError { detail } => match detail.kind() {
+            std::io::ErrorKind::ConnectionRefused => "connection_refused",
+            std::io::ErrorKind::TimedOut => "timeout",
+            std::io::ErrorKind::ConnectionReset
+            | std::io::ErrorKind::ConnectionAborted
+            | std::io::ErrorKind::BrokenPipe
+            | std::io::ErrorKind::UnexpectedEof => "connection_closed",
+            _ => "io",
+        },
+        neo4rs::Error::ConnectionError => "connection",
+        neo4rs::Error::AuthenticationError(_) => "authentication",
+        neo4rs::Error::UrlParseError(_)
+        | neo4rs::Error::UnsupportedScheme(_)
+        | neo4rs::Error::InvalidDnsName(_)
+        | neo4rs::Error::InvalidConfig => "configuration",
+        neo4rs::Error::StringTooLong
+        | neo4rs::Error::MapTooBig
+        | neo4rs::Error::BytesTooBig
+        | neo4rs::Error::ListTooLong => "serialization",
+        neo4rs::Error::ConversionError | neo4rs::Error::DeserializationError(_) => {
+            "deserialization"
+        }
+        _ => "protocol",
+    }
+}
+
+/// `exception.type` value for an error
+///
+/// Errors reported by the server use their Neo4j status code, e.g.
+/// `Neo.ClientError.Statement.SyntaxError`; driver errors use the `neo4rs::Error` variant
+/// they were raised as, e.g. `neo4rs::Error::ConnectionError`.
+///
+/// # Example
+///
+/// ```rust
+/// use otel_instrumentation_neo4jrs::error::exception_type;
+///
+/// assert_eq!(
+///     exception_type(&neo4rs::Error::ConnectionError),
+///     "neo4rs::Error::ConnectionError"
+/// );
+/// ```
+#[must_use]
+pub fn exception_type(err: &neo4rs::Error) -> &str {
+    match err {
+        neo4rs::Error::Neo4j(e) => e.code(),
+        neo4rs::Error::IOError { .. } => "neo4rs::Error::IOError",
+        neo4rs::Error::UrlParseError(_) => "neo4rs::Error::UrlParseError",
+        neo4rs::Error::UnsupportedScheme(_) => "neo4rs::Error::UnsupportedScheme",
+        neo4rs::Error::InvalidDnsName(_) => "neo4rs::Error::InvalidDnsName",
+        neo4rs::Error::ConnectionError => "neo4rs::Error::ConnectionError",
+        neo4rs::Error::StringTooLong => "neo4rs::Error::StringTooLong",
+        neo4rs::Error::MapTooBig => "neo4rs::Error::MapTooBig",
+        neo4rs::Error::BytesTooBig => "neo4rs::Error::BytesTooBig",
+        neo4rs::Error::ListTooLong => "neo4rs::Error::ListTooLong",
+        neo4rs::Error::InvalidConfig => "neo4rs::Error::InvalidConfig",
+        neo4rs::Error::UnsupportedVersion(_) => "neo4rs::Error::UnsupportedVersion",
+        neo4rs::Error::UnexpectedMessage(_) => "neo4rs::Error::UnexpectedMessage",
+        neo4rs::Error::UnknownType(_) => "neo4rs::Error::UnknownType",
+        neo4rs::Error::UnknownMessage(_) => "neo4rs::Error::UnknownMessage",
+        neo4rs::Error::ConversionError => "neo4rs::Error::ConversionError",
+        neo4rs::Error::AuthenticationError(_) => "neo4rs::Error::AuthenticationError",
+        neo4rs::Error::InvalidTypeMarker(_) => "neo4rs::Error::InvalidTypeMarker",
+        neo4rs::Error::DeserializationError(_) => "neo4rs::Error::DeserializationError",
+        _ => "neo4rs::Error",
+    }
+}
+
+/// Whether retrying the operation that failed with `err` may succeed
+///
+/// True for Neo4j transient errors such as `Neo.TransientError.Transaction.DeadlockDetected`,
+/// cluster errors raised while leadership or routing changes (`Neo.ClusterError.*`,
+/// `Neo.ClientError.Cluster.NotALeader`, writes hitting a read-only follower) and dropped
+/// connections. Transactions terminated on purpose are not retryable even though the server
+/// reports them as transient.
+///
+/// # Example
+///
+/// ```rust
+/// use otel_instrumentation_neo4jrs::error::is_retryable;
+///
+/// assert!(is_retryable(&neo4rs::Error::ConnectionError));
+/// assert!(!is_retryable(&neo4rs::Error::AuthenticationError("denied".into())));
+/// ```
+#[must_use]
+pub fn is_retryable(err: &neo4rs::Error) -> bool {
+    match err {
+        neo4rs::Error::Neo4j(e) => is_retryable_code(e.code()),
+        neo4rs::Error::IOError { .. } | neo4rs::Error::ConnectionError => true,
+        _ => false,
+    }
+}
+
+/// Whether a Neo4j status code marks a failure that may go away on retry
+fn is_retryable_code(code: &str) -> bool {
+    match code {
+        "Neo.TransientError.Transaction.Terminated"
+        | "Neo.TransientError.Transaction.LockClientStopped" => false,
+        "Neo.ClientError.Cluster.NotALeader"
+        | "Neo.ClientError.General.ForbiddenOnReadOnlyDatabase" => true,
+        _ => matches!(
+            code.split('.').nth(1),
+            Some("TransientError" | "ClusterError")
+        ),
+    }
+}
+
+/// `error.type` value for a Neo4j status code
+fn classify_code(code: &str) -> &'static str {
+    let mut segments = code.split('.').skip(1);
+    let (class, category, title) = (segments.next(), segments.next(), segments.next());
+    match (class, category, title) {
+        (_, Some("Statement"), Some("SyntaxError")) => "syntax_error",
+        (_, Some("Schema"), Some(title)) if title.starts_with("Constraint") => {
+            "constraint_violation"
+        }
+        (_, Some("Security"), Some("Unauthorized" | "AuthenticationRateLimit")) => "authentication",
+        (_, Some("Security"), _) => "forbidden",
+        (_, Some("Transaction"), Some(title)) if title.starts_with("TransactionTimedOut") => {
+            "timeout"
+        }
+        (_, Some("Database"), Some("DatabaseNotFound")) => "database_not_found",
+        (Some("TransientError"), _, _) => "transient",
+        (Some("ClientError"), _, _) => "client_error",
+        (Some("DatabaseError"), _, _) => "database_error",
+        _ => "unknown",
+    }
+}
+
+/// Category segment of a Neo4j status code, or `unknown` if the code is malformed
+fn code_category(code: &str) -> &str {
+    code.split('.').nth(2).unwrap_or("unknown")
+}
+
+/// Classification segment of a Neo4j status code
+fn code_class(code: &str) -> &'static str {
+    match code.split('.').nth(1) {
+        Some("ClientError") => "ClientError",
+        Some("TransientError") => "TransientError",
+        Some("DatabaseError") => "DatabaseError",
+        _ => "UnknownError",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_class() {
+        assert_eq!(
+            code_class("Neo.ClientError.Statement.SyntaxError"),
+            "ClientError"
+        );
+        assert_eq!(
+            code_class("Neo.ClientError.Security.Unauthorized"),
+            "ClientError"
+        );
+        assert_eq!(
+            code_class("Neo.TransientError.Transaction.DeadlockDetected"),
+            "TransientError"
+        );
+        assert_eq!(
+            code_class("Neo.TransientError.General.DatabaseUnavailable"),
+            "TransientError"
+        );
+        assert_eq!(
+            code_class("Neo.DatabaseError.General.UnknownError"),
+            "DatabaseError"
+        );
+        assert_eq!(
+            code_class("Neo.DatabaseError.Statement.ExecutionFailed"),
+            "DatabaseError"
+        );
+        assert_eq!(code_class("garbage"), "UnknownError");
+    }
+
+    #[test]
+    fn test_code_category() {
+        assert_eq!(
+            code_category("Neo.ClientError.Statement.SyntaxError"),
+            "Statement"
+        );
+        assert_eq!(
+            code_category("Neo.TransientError.Transaction.DeadlockDetected"),
+            "Transaction"
+        );
+        assert_eq!(code_category("Neo.ClientError"), "unknown");
+    }
+
+    #[test]
+    fn test_classify_code() {
+        assert_eq!(
+            classify_code("Neo.ClientError.Statement.SyntaxError"),
+            "syntax_error"
+        );
+        assert_eq!(
+            classify_code("Neo.ClientError.Schema.ConstraintValidationFailed"),
+            "constraint_violation"
+        );
+        assert_eq!(
+            classify_code("Neo.TransientError.Transaction.DeadlockDetected"),
+            "transient"
+        );
+        assert_eq!(
+            classify_code("Neo.TransientError.General.DatabaseUnavailable"),
+            "transient"
+        );
+        assert_eq!(
+            classify_code("Neo.ClientError.Transaction.TransactionTimedOut"),
+            "timeout"
+        );
+        assert_eq!(
+            classify_code("Neo.ClientError.Security.Unauthorized"),
+            "authentication"
+        );
+        assert_eq!(
+            classify_code("Neo.ClientError.Security.Forbidden"),
+            "forbidden"
+        );
+        assert_eq!(
+            classify_code("Neo.ClientError.Database.DatabaseNotFound"),
+            "database_not_found"
+        );
+        assert_eq!(
+            classify_code("Neo.ClientError.Statement.EntityNotFound"),
+            "client_error"
+        );
+        assert_eq!(
+            classify_code("Neo.DatabaseError.General.UnknownError"),
+            "database_error"
+        );
+        assert_eq!(classify_code("garbage"), "unknown");
+    }
+
+    #[test]
+    fn test_classify_driver_errors() {
+        let io = |kind: std::io::ErrorKind| neo4rs::Error::from(std::io::Error::from(kind));
+
+        assert_eq!(
+            classify_error(&io(std::io::ErrorKind::ConnectionRefused)),
+            "connection_refused"
+        );
+        assert_eq!(classify_error(&io(std::io::ErrorKind::TimedOut)), "timeout");
+        assert_eq!(
+            classify_error(&io(std::io::ErrorKind::ConnectionReset)),
+            "connection_closed"
+        );
+        assert_eq!(classify_error(&io(std::io::ErrorKind::Other)), "io");
+        assert_eq!(
+            classify_error(&neo4rs::Error::ConnectionError),
+            "connection"
+        );
+        assert_eq!(
+            classify_error(&neo4rs::Error::AuthenticationError("denied".into())),
+            "authentication"
+        );
+        assert_eq!(
+            classify_error(&neo4rs::Error::InvalidConfig),
+            "configuration"
+        );
+        assert_eq!(
+            classify_error(&neo4rs::Error::ConversionError),
+            "deserialization"
+        );
+        assert_eq!(
+            classify_error(&neo4rs::Error::UnexpectedMessage("FAILURE".into())),
+            "protocol"
+        );
+    }
+
+    #[test]
+    fn test_exception_type_of_driver_errors() {
+        assert_eq!(
+            exception_type(&std::io::Error::from(std::io::ErrorKind::TimedOut).into()),
+            "neo4rs::Error::IOError"
+        );
+        assert_eq!(
+            exception_type(&neo4rs::Error::AuthenticationError("denied".into())),
+            "neo4rs::Error::AuthenticationError"
+        );
+        assert_eq!(
+            exception_type(&neo4rs::Error::UnexpectedMessage("FAILURE".into())),
+            "neo4rs::Error::UnexpectedMessage"
+        );
+    }
+
+    #[test]
+    fn test_retryable_codes() {
+        assert!(is_retryable_code(
+            "Neo.TransientError.Transaction.DeadlockDetected"
+        ));
+        assert!(is_retryable_code(
+            "Neo.TransientError.General.DatabaseUnavailable"
+        ));
+        assert!(is_retryable_code("Neo.ClusterError.Routing"));
+        assert!(is_retryable_code("Neo.ClientError.Cluster.NotALeader"));
+        assert!(is_retryable_code(
+            "Neo.ClientError.General.ForbiddenOnReadOnlyDatabase"
+        ));
+        assert!(!is_retryable_code(
+            "Neo.TransientError.Transaction.Terminated"
+        ));
+        assert!(!is_retryable_code("Neo.ClientError.Statement.SyntaxError"));
+        assert!(!is_retryable_code("Neo.DatabaseError.General.UnknownError"));
+    }
+
+    #[test]
+    fn test_retryable_driver_errors() {
+        assert!(is_retryable(&neo4rs::Error::ConnectionError));
+        assert!(is_retryable(
+            &std::io::Error::from(std::io::ErrorKind::ConnectionReset).into()
+        ));
+        assert!(!is_retryable(&neo4rs::Error::AuthenticationError(
+            "denied".into()
+        )));
+        assert!(!is_retryable(&neo4rs::Error::ConversionError));
+    }
+
+    #[test]
+    fn test_driver_errors() {
+        let err = neo4rs::Error::ConnectionError;
+        assert_eq!(error_class(&err), DRIVER_ERROR);
+        assert_eq!(error_category(&err), "connection");
+
+        let err = neo4rs::Error::AuthenticationError("bad credentials".into());
+        assert_eq!(error_class(&err), DRIVER_ERROR);
+        assert_eq!(error_category(&err), "authentication");
+
+        let err = neo4rs::Error::UnsupportedScheme("http".into());
+        assert_eq!(error_category(&err), "configuration");
+    }
+}
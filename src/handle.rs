@@ -0,0 +1,104 @@
+//! Object-safe access to an instrumented graph
+//!
+//! [`InstrumentedGraph`]'s methods are `async fn`s, so it cannot be stored as a trait object
+//! directly. [`InstrumentedGraphHandle`] exposes its core operations with boxed futures
+//! instead, for applications that inject database handles as `Arc<dyn Trait>`, e.g. from a
+//! dependency injection container, or that swap in a fake in tests.
+
+use crate::graph::InstrumentedGraph;
+use crate::stream::InstrumentedRowStream;
+use crate::txn::InstrumentedTxn;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use neo4rs::Query;
+
+/// The core operations of an [`InstrumentedGraph`], usable behind `dyn`
+///
+/// Each method behaves like the `InstrumentedGraph` method of the same name, recording the
+/// same spans and metrics. Get one with [`InstrumentedGraph::boxed`], or convert that into an
+/// `Arc` to share it.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use otel_instrumentation_neo4jrs::{InstrumentedGraph, InstrumentedGraphHandle};
+/// use std::sync::Arc;
+///
+/// struct AppState {
+///     db: Arc<dyn InstrumentedGraphHandle>,
+/// }
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let graph = InstrumentedGraph::connect("bolt://localhost:7687", "neo4j", "password").await?;
+/// let state = AppState {
+///     db: Arc::from(graph.boxed()),
+/// };
+///
+/// state.db.run(neo4rs::query("CREATE (:Visit)")).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait InstrumentedGraphHandle: Send + Sync {
+    /// Runs a query on the configured database, like [`InstrumentedGraph::run`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query execution fails
+    fn run(&self, query: Query) -> BoxFuture<'_, Result<(), neo4rs::Error>>;
+
+    /// Executes a query on the configured database, like [`InstrumentedGraph::execute`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query execution fails
+    fn execute(&self, query: Query) -> BoxFuture<'_, Result<InstrumentedRowStream, neo4rs::Error>>;
+
+    /// Starts a new transaction, like [`InstrumentedGraph::start_txn`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be started
+    fn start_txn(&self) -> BoxFuture<'_, Result<InstrumentedTxn, neo4rs::Error>>;
+}
+
+impl InstrumentedGraphHandle for InstrumentedGraph {
+    fn run(&self, query: Query) -> BoxFuture<'_, Result<(), neo4rs::Error>> {
+        InstrumentedGraph::run(self, query).boxed()
+    }
+
+    fn execute(&self, query: Query) -> BoxFuture<'_, Result<InstrumentedRowStream, neo4rs::Error>> {
+        InstrumentedGraph::execute(self, query).boxed()
+    }
+
+    fn start_txn(&self) -> BoxFuture<'_, Result<InstrumentedTxn, neo4rs::Error>> {
+        InstrumentedGraph::start_txn(self).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::Reject;
+    use crate::test_support::CapturedSpans;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_handle_delegates_to_graph() {
+        let capture = CapturedSpans::install();
+        let graph = InstrumentedGraph::connect("bolt://127.0.0.1:1", "neo4j", "password")
+            .await
+            .unwrap()
+            .with_middleware(Arc::new(Reject));
+        let handle: Arc<dyn InstrumentedGraphHandle> = Arc::from(graph.boxed());
+
+        // Nothing listens on port 1 and the middleware rejects queries before the driver
+        assert!(handle.start_txn().await.is_err());
+        assert!(matches!(
+            handle.run(neo4rs::query("RETURN 1")).await,
+            Err(neo4rs::Error::InvalidConfig)
+        ));
+
+        capture.span("neo4j.start_txn");
+        capture.span("neo4j.run");
+    }
+}
@@ -47,19 +47,50 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 - **Drop-in replacement** - Implements `Deref` and `AsRef<Graph>` for compatibility
 - **Automatic instrumentation** - All methods use `#[instrument]` with OpenTelemetry semantic conventions
 - **Zero overhead when disabled** - Tracing macros compile to no-ops when not enabled
+- **Optional metrics** - The default `metrics` cargo feature adds the `metrics` module; build
+  with `default-features = false` for tracing only
 
 # Limitations
 
-Due to neo4rs API limitations, query text and parameters cannot be extracted for tracing.
-Span names default to the method being called (execute, run, `start_txn`, etc.).
+`neo4rs::Query` keeps its text and parameters private, so query text is only recorded for
+queries built with [`query::TracedQuery`] (or annotated from one as a [`query::AnnotatedQuery`]). Span names default to
+`neo4j.<method>` (`neo4j.execute`, `neo4j.run`, `neo4j.start_txn`, etc.), with the `neo4j`
+prefix configurable through `InstrumentedGraph::with_span_prefix`.
 
 */
 #![warn(clippy::all, clippy::pedantic)]
 
+pub mod builder;
+pub mod env;
+pub mod error;
 pub mod graph;
+pub mod handle;
+#[cfg(feature = "metrics")]
 pub mod metrics;
+pub mod middleware;
+pub mod query;
+pub mod redact;
+pub mod retry;
+mod stream;
+pub mod telemetry;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
 pub mod txn;
 
-pub use graph::InstrumentedGraph;
-pub use metrics::{MetricsBuilder, Neo4jMetrics};
+#[cfg(test)]
+mod test_support;
+
+pub use builder::{InstrumentedGraphBuilder, InstrumentedGraphConfig};
+pub use env::EnvConfigError;
+pub use graph::{InstrumentedGraph, InstrumentedGraphParts};
+pub use handle::InstrumentedGraphHandle;
+#[cfg(feature = "metrics")]
+pub use metrics::{
+    MetricsBuilder, MetricsSnapshot, NamingConvention, Neo4jMetrics, PoolStats, PoolStatsProvider,
+};
+pub use middleware::{Next, QueryMiddleware};
+pub use redact::{MaskAllRedactor, RedactAction, Redactor};
+pub use retry::RetryPolicy;
+pub use stream::InstrumentedRowStream;
+pub use telemetry::{OperationContext, TelemetryConfig, DEFAULT_SPAN_PREFIX};
 pub use txn::InstrumentedTxn;
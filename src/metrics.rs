@@ -3,10 +3,21 @@
 //! This module provides comprehensive metrics collection for Neo4j database operations,
 //! including query execution times, transaction durations, error rates, and connection statistics.
 
-use opentelemetry::metrics::{Counter, Histogram, Meter, UpDownCounter};
+use crate::error::{classify_error, error_class};
+use crate::query::AccessMode;
+use neo4rs::Row;
+use opentelemetry::metrics::{Counter, Histogram, Meter, ObservableGauge, UpDownCounter};
 use opentelemetry::KeyValue;
+use serde::de::{
+    Deserialize, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 /// Neo4j metrics collector that tracks various database operation metrics
 #[derive(Clone, Debug)]
@@ -23,10 +34,216 @@ pub struct Neo4jMetrics {
     errors_total: Counter<u64>,
     /// Number of active database connections
     active_connections: UpDownCounter<i64>,
+    /// Lifetime of connections from metrics being enabled until drop, in milliseconds
+    connection_duration: Histogram<f64>,
     /// Number of transaction commits
     transaction_commits: Counter<u64>,
     /// Number of transaction rollbacks
     transaction_rollbacks: Counter<u64>,
+    /// Queries run per committed or rolled back transaction
+    transaction_queries: Histogram<u64>,
+    /// Row throughput of drained result streams
+    query_rows_per_second: Histogram<f64>,
+    /// Rows returned per result stream
+    query_rows: Histogram<u64>,
+    /// Approximate size of the rows returned per result stream, in bytes
+    query_response_bytes: Histogram<u64>,
+    /// Time until `execute` returned its result stream, in milliseconds
+    query_dispatch_duration: Histogram<f64>,
+    /// Time from result stream creation to exhaustion, in milliseconds
+    query_stream_duration: Histogram<f64>,
+    /// Number of connection attempts retried by `connect_with_retry`
+    connect_retries: Counter<u64>,
+    /// Number of query attempts retried by `run_with_retry`
+    query_retries: Counter<u64>,
+    /// Length of traced statement text, in characters
+    query_text_length: Histogram<u64>,
+    /// Number of queries slower than `slow_query_threshold`
+    slow_queries: Counter<u64>,
+    /// Queries taking longer than this are counted in `slow_queries`
+    slow_query_threshold: Option<Duration>,
+    /// Databases recorded by name; others are recorded as [`OTHER_DATABASE`]
+    database_allowlist: Option<Arc<HashSet<String>>>,
+    /// Pool connections currently checked out, read from the [`PoolStatsProvider`]
+    _pool_in_use: ObservableGauge<i64>,
+    /// Pool connections currently idle, read from the [`PoolStatsProvider`]
+    _pool_idle: ObservableGauge<i64>,
+    /// Cumulative totals shared between clones, readable without a metrics backend
+    totals: Arc<Totals>,
+    /// Static attributes added to every data point, see [`MetricsBuilder::with_attribute`]
+    attributes: Arc<[KeyValue]>,
+}
+
+/// Connection pool occupancy reported by a [`PoolStatsProvider`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Connections currently checked out of the pool
+    pub in_use: i64,
+    /// Connections open but not checked out
+    pub idle: i64,
+}
+
+/// Source of connection pool statistics for the `neo4j.pool.*` gauges
+///
+/// neo4rs does not expose its pool internals, so the gauges can only report what the
+/// application knows, e.g. from its own pool or connection accounting. Without a provider
+/// the gauges report zero.
+///
+/// # Example
+///
+/// ```rust
+/// use otel_instrumentation_neo4jrs::metrics::{PoolStats, PoolStatsProvider};
+/// use std::sync::atomic::{AtomicI64, Ordering};
+///
+/// struct Tracked {
+///     checked_out: AtomicI64,
+///     max_size: i64,
+/// }
+///
+/// impl PoolStatsProvider for Tracked {
+///     fn pool_stats(&self) -> PoolStats {
+///         let in_use = self.checked_out.load(Ordering::Relaxed);
+///         PoolStats { in_use, idle: self.max_size - in_use }
+///     }
+/// }
+/// ```
+pub trait PoolStatsProvider: Send + Sync {
+    /// Current pool occupancy; called on every metrics collection
+    fn pool_stats(&self) -> PoolStats;
+}
+
+/// Gauge reporting the `value` of the provider's [`PoolStats`] on every collection
+fn pool_gauge(
+    meter: &Meter,
+    name: Cow<'static, str>,
+    description: &'static str,
+    pool_stats: Option<Arc<dyn PoolStatsProvider>>,
+    attributes: Arc<[KeyValue]>,
+    value: fn(PoolStats) -> i64,
+) -> ObservableGauge<i64> {
+    meter
+        .i64_observable_gauge(name)
+        .with_description(description)
+        .with_callback(move |observer| {
+            let stats = pool_stats
+                .as_ref()
+                .map(|p| p.pool_stats())
+                .unwrap_or_default();
+            observer.observe(value(stats), &attributes);
+        })
+        .build()
+}
+
+/// Running totals kept alongside the OpenTelemetry instruments
+#[derive(Debug, Default)]
+struct Totals {
+    queries: AtomicU64,
+    errors: AtomicU64,
+    transactions: AtomicU64,
+    connections: AtomicI64,
+}
+
+/// Point-in-time copy of the cumulative totals observed by a [`Neo4jMetrics`] instance
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// Queries recorded, successful or not
+    pub queries_total: u64,
+    /// Errors recorded, including failed queries
+    pub errors_total: u64,
+    /// Transactions started
+    pub transactions_total: u64,
+    /// Connections currently open, the value of `neo4j.connections.active`
+    pub active_connections: i64,
+}
+
+/// `database` attribute value for databases missing from the allowlist, see
+/// [`MetricsBuilder::with_database_allowlist`]
+pub const OTHER_DATABASE: &str = "_other";
+
+/// Default bucket boundaries, in milliseconds, for the query and transaction duration histograms
+pub const DEFAULT_DURATION_BOUNDARIES: &[f64] = &[
+    0.5, 1.0, 2.5, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0,
+];
+
+/// Bucket boundaries, in milliseconds, for `neo4j.connection.duration`: one second to a day
+const CONNECTION_DURATION_BOUNDARIES: &[f64] = &[
+    1_000.0,
+    10_000.0,
+    60_000.0,
+    600_000.0,
+    3_600_000.0,
+    21_600_000.0,
+    86_400_000.0,
+];
+
+/// Bucket boundaries for `neo4j.query.response.bytes`: 1 KiB to 64 MiB in steps of four
+const RESPONSE_BYTES_BOUNDARIES: &[f64] = &[
+    1_024.0,
+    4_096.0,
+    16_384.0,
+    65_536.0,
+    262_144.0,
+    1_048_576.0,
+    4_194_304.0,
+    16_777_216.0,
+    67_108_864.0,
+];
+
+/// How instrument names are spelled when they are registered with the meter
+///
+/// # Example
+///
+/// ```rust
+/// use otel_instrumentation_neo4jrs::metrics::NamingConvention;
+///
+/// assert_eq!(
+///     NamingConvention::Prometheus.counter("neo4j.transaction.commits"),
+///     "neo4j_transaction_commits_total"
+/// );
+/// assert_eq!(
+///     NamingConvention::Otlp.counter("neo4j.transaction.commits"),
+///     "neo4j.transaction.commits"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NamingConvention {
+    /// Dotted OpenTelemetry names such as `neo4j.query.duration`
+    #[default]
+    Otlp,
+    /// Underscored names with Prometheus suffixes: counters end in `_total` and duration
+    /// histograms in `_milliseconds`, e.g. `neo4j_query_duration_milliseconds`
+    Prometheus,
+}
+
+impl NamingConvention {
+    /// Registered name of the instrument `name`
+    #[must_use]
+    pub fn name(self, name: &'static str) -> Cow<'static, str> {
+        match self {
+            Self::Otlp => Cow::Borrowed(name),
+            Self::Prometheus => Cow::Owned(name.replace('.', "_")),
+        }
+    }
+
+    /// Registered name of the counter `name`
+    #[must_use]
+    pub fn counter(self, name: &'static str) -> Cow<'static, str> {
+        self.with_suffix(name, "_total")
+    }
+
+    /// Registered name of the millisecond duration histogram `name`
+    #[must_use]
+    pub fn duration(self, name: &'static str) -> Cow<'static, str> {
+        self.with_suffix(name, "_milliseconds")
+    }
+
+    fn with_suffix(self, name: &'static str, suffix: &str) -> Cow<'static, str> {
+        let name = self.name(name);
+        match self {
+            Self::Prometheus if !name.ends_with(suffix) => Cow::Owned(name.into_owned() + suffix),
+            _ => name,
+        }
+    }
 }
 
 impl Neo4jMetrics {
@@ -48,46 +265,160 @@ impl Neo4jMetrics {
     /// ```
     #[must_use]
     pub fn new(meter: &Meter) -> Self {
+        Self::with_pool_stats(meter, None)
+    }
+
+    /// Create a metrics instance whose pool gauges read from `pool_stats`
+    ///
+    /// `neo4j.pool.in_use` and `neo4j.pool.idle` report zero when `pool_stats` is `None`.
+    #[must_use]
+    pub fn with_pool_stats(meter: &Meter, pool_stats: Option<Arc<dyn PoolStatsProvider>>) -> Self {
+        let options = MetricsBuilder {
+            pool_stats,
+            ..MetricsBuilder::new()
+        };
+        Self::with_options(meter, &options)
+    }
+
+    /// Create a metrics instance with the settings collected by `options`
+    // One initializer per instrument; splitting it up would only scatter them
+    #[allow(clippy::too_many_lines)]
+    fn with_options(meter: &Meter, options: &MetricsBuilder) -> Self {
+        let naming = options.naming;
+        let duration_boundaries = options.duration_boundaries.clone();
+        let attributes: Arc<[KeyValue]> = Arc::from(options.attributes.as_slice());
+        let pool_stats = options.pool_stats.clone();
         Self {
             queries_total: meter
-                .u64_counter("neo4j.queries.total")
+                .u64_counter(naming.counter("neo4j.queries.total"))
                 .with_description("Total number of Neo4j queries executed")
                 .build(),
 
             query_duration: meter
-                .f64_histogram("neo4j.query.duration")
+                .f64_histogram(naming.duration("neo4j.query.duration"))
                 .with_description("Duration of Neo4j query execution in milliseconds")
+                .with_unit("ms")
+                .with_boundaries(duration_boundaries.clone())
                 .build(),
 
             transactions_total: meter
-                .u64_counter("neo4j.transactions.total")
+                .u64_counter(naming.counter("neo4j.transactions.total"))
                 .with_description("Total number of Neo4j transactions started")
                 .build(),
 
             transaction_duration: meter
-                .f64_histogram("neo4j.transaction.duration")
+                .f64_histogram(naming.duration("neo4j.transaction.duration"))
                 .with_description("Duration of Neo4j transactions in milliseconds")
+                .with_unit("ms")
+                .with_boundaries(duration_boundaries)
                 .build(),
 
             errors_total: meter
-                .u64_counter("neo4j.errors.total")
+                .u64_counter(naming.counter("neo4j.errors.total"))
                 .with_description("Total number of Neo4j errors encountered")
                 .build(),
 
             active_connections: meter
-                .i64_up_down_counter("neo4j.connections.active")
+                .i64_up_down_counter(naming.name("neo4j.connections.active"))
                 .with_description("Number of active Neo4j connections")
                 .build(),
 
+            connection_duration: meter
+                .f64_histogram(naming.duration("neo4j.connection.duration"))
+                .with_description("Lifetime of Neo4j connections in milliseconds")
+                .with_unit("ms")
+                .with_boundaries(CONNECTION_DURATION_BOUNDARIES.to_vec())
+                .build(),
+
             transaction_commits: meter
-                .u64_counter("neo4j.transaction.commits")
+                .u64_counter(naming.counter("neo4j.transaction.commits"))
                 .with_description("Number of successful transaction commits")
                 .build(),
 
             transaction_rollbacks: meter
-                .u64_counter("neo4j.transaction.rollbacks")
+                .u64_counter(naming.counter("neo4j.transaction.rollbacks"))
                 .with_description("Number of transaction rollbacks")
                 .build(),
+
+            transaction_queries: meter
+                .u64_histogram(naming.name("neo4j.transaction.queries"))
+                .with_description("Number of queries run in each Neo4j transaction")
+                .with_boundaries(vec![1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 1000.0])
+                .build(),
+
+            query_rows_per_second: meter
+                .f64_histogram(naming.name("neo4j.query.rows_per_second"))
+                .with_description("Rows consumed per second from drained Neo4j result streams")
+                .build(),
+
+            query_rows: meter
+                .u64_histogram(naming.name("neo4j.query.rows"))
+                .with_description("Number of rows consumed from each Neo4j result stream")
+                .build(),
+
+            query_response_bytes: meter
+                .u64_histogram(naming.name("neo4j.query.response.bytes"))
+                .with_description("Approximate size of the rows of each Neo4j result stream")
+                .with_unit("By")
+                .with_boundaries(RESPONSE_BYTES_BOUNDARIES.to_vec())
+                .build(),
+
+            query_dispatch_duration: meter
+                .f64_histogram(naming.duration("neo4j.query.dispatch.duration"))
+                .with_description("Time until a Neo4j result stream is available, in milliseconds")
+                .with_unit("ms")
+                .build(),
+
+            query_stream_duration: meter
+                .f64_histogram(naming.duration("neo4j.query.stream.duration"))
+                .with_description(
+                    "Time from Neo4j result stream creation to exhaustion, in milliseconds",
+                )
+                .with_unit("ms")
+                .build(),
+
+            connect_retries: meter
+                .u64_counter(naming.counter("neo4j.connect.retries"))
+                .with_description("Number of Neo4j connection attempts that were retried")
+                .build(),
+
+            query_retries: meter
+                .u64_counter(naming.counter("neo4j.query.retries"))
+                .with_description("Number of Neo4j query attempts that were retried")
+                .build(),
+
+            query_text_length: meter
+                .u64_histogram(naming.name("neo4j.query.text.length"))
+                .with_description("Length of traced Neo4j statement text in characters")
+                .build(),
+
+            slow_queries: meter
+                .u64_counter(naming.counter("neo4j.queries.slow"))
+                .with_description("Number of Neo4j queries slower than the slow query threshold")
+                .build(),
+            slow_query_threshold: options.slow_query_threshold,
+            database_allowlist: options.database_allowlist.clone().map(Arc::new),
+
+            _pool_in_use: pool_gauge(
+                meter,
+                naming.name("neo4j.pool.in_use"),
+                "Neo4j pool connections currently in use",
+                pool_stats.clone(),
+                attributes.clone(),
+                |stats| stats.in_use,
+            ),
+
+            _pool_idle: pool_gauge(
+                meter,
+                naming.name("neo4j.pool.idle"),
+                "Neo4j pool connections currently idle",
+                pool_stats,
+                attributes.clone(),
+                |stats| stats.idle,
+            ),
+
+            totals: Arc::default(),
+            attributes,
         }
     }
 
@@ -106,35 +437,220 @@ impl Neo4jMetrics {
         operation: Option<&str>,
         database: &str,
     ) {
-        let mut attributes = vec![
-            KeyValue::new("success", success),
-            KeyValue::new("database", database.to_string()),
-        ];
+        self.record_query_as(duration, success, operation, database, None);
+    }
 
-        if let Some(op) = operation {
-            attributes.push(KeyValue::new("operation", op.to_string()));
-        }
+    /// [`record_query`](Self::record_query), labelling the data points with the statement's
+    /// `access_mode` when it is known
+    pub(crate) fn record_query_as(
+        &self,
+        duration: Duration,
+        success: bool,
+        operation: Option<&str>,
+        database: &str,
+        access_mode: Option<AccessMode>,
+    ) {
+        let attributes = self.query_attributes(success, operation, database, access_mode);
 
         self.queries_total.add(1, &attributes);
+        self.totals.queries.fetch_add(1, Ordering::Relaxed);
         // Convert duration to milliseconds safely
         // For durations up to ~24 days, this will be accurate to the millisecond
         let millis = duration.as_secs_f64() * 1000.0;
-        self.query_duration.record(millis, &attributes);
+        in_span_context(|| self.query_duration.record(millis, &attributes));
+        self.record_if_slow(duration, operation, database);
 
         if !success {
             self.errors_total.add(1, &attributes);
+            self.totals.errors.fetch_add(1, Ordering::Relaxed);
         }
     }
 
+    /// Record a failed query, labelling the error count with its type and class
+    ///
+    /// Counts the query like [`record_query`](Self::record_query) with `success = false`,
+    /// but the `errors_total` data point also carries `error_type` (the semantic value from
+    /// [`classify_error`], e.g. `syntax_error`) and `error_class` (`ClientError`, `TransientError`,
+    /// `DatabaseError` or `DriverError`), so dashboards can separate client mistakes from
+    /// server and database faults.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - The duration of the query execution
+    /// * `error` - The error the query failed with
+    /// * `operation` - The type of operation
+    /// * `database` - The database name
+    pub fn record_query_failure(
+        &self,
+        duration: Duration,
+        error: &neo4rs::Error,
+        operation: Option<&str>,
+        database: &str,
+    ) {
+        self.record_query_failure_as(duration, error, operation, database, None);
+    }
+
+    /// [`record_query_failure`](Self::record_query_failure), labelling the data points with
+    /// the statement's `access_mode` when it is known
+    pub(crate) fn record_query_failure_as(
+        &self,
+        duration: Duration,
+        error: &neo4rs::Error,
+        operation: Option<&str>,
+        database: &str,
+        access_mode: Option<AccessMode>,
+    ) {
+        let attributes = self.query_attributes(false, operation, database, access_mode);
+
+        self.queries_total.add(1, &attributes);
+        self.totals.queries.fetch_add(1, Ordering::Relaxed);
+        let millis = duration.as_secs_f64() * 1000.0;
+        in_span_context(|| self.query_duration.record(millis, &attributes));
+        self.record_if_slow(duration, operation, database);
+
+        self.count_error(attributes, error);
+    }
+
+    /// Count an error that happened after the query was already counted, e.g. a result
+    /// stream that failed mid-fetch
+    ///
+    /// The data point carries the same labels as a failed query's, so `errors_total` and
+    /// `queries_total` can be divided by `operation` and `database`.
+    pub(crate) fn record_failure(
+        &self,
+        error: &neo4rs::Error,
+        operation: Option<&str>,
+        database: &str,
+        access_mode: Option<AccessMode>,
+    ) {
+        let attributes = self.query_attributes(false, operation, database, access_mode);
+        self.count_error(attributes, error);
+    }
+
+    /// Add `error_type` and `error_class` to the attributes of a failed query and count it
+    fn count_error(&self, mut attributes: Vec<KeyValue>, error: &neo4rs::Error) {
+        attributes.push(KeyValue::new("error_type", classify_error(error)));
+        attributes.push(KeyValue::new("error_class", error_class(error)));
+        self.errors_total.add(1, &attributes);
+        self.totals.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record the length of a statement's text
+    ///
+    /// Only statements whose text is known, i.e. traced and annotated queries, can be
+    /// recorded; the text itself is never part of the data point.
+    ///
+    /// # Arguments
+    ///
+    /// * `chars` - The length of the statement text in characters
+    /// * `operation` - The statement's operation, e.g. `MERGE Person`
+    /// * `database` - The database name
+    pub fn record_query_text_length(&self, chars: u64, operation: Option<&str>, database: &str) {
+        self.query_text_length
+            .record(chars, &self.operation_attributes(operation, database));
+    }
+
+    /// Record the number of rows consumed from a result stream
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The number of rows consumed, which is partial if the stream was dropped early
+    /// * `operation` - The operation that produced the stream
+    /// * `database` - The database name
+    pub fn record_returned_rows(&self, rows: u64, operation: Option<&str>, database: &str) {
+        self.query_rows
+            .record(rows, &self.operation_attributes(operation, database));
+    }
+
+    /// Record the approximate size of the rows consumed from a result stream
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The summed [`approximate_row_bytes`] of the rows, partial if the stream was
+    ///   dropped early
+    /// * `operation` - The operation that produced the stream
+    /// * `database` - The database name
+    pub fn record_response_bytes(&self, bytes: u64, operation: Option<&str>, database: &str) {
+        self.query_response_bytes
+            .record(bytes, &self.operation_attributes(operation, database));
+    }
+
+    /// Record how long it took to obtain a result stream
+    ///
+    /// For streaming operations `neo4j.query.duration` only covers this dispatch phase;
+    /// rows are fetched afterwards and measured by
+    /// [`record_stream_duration`](Self::record_stream_duration).
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - Time from issuing the query to receiving the stream
+    /// * `operation` - The operation that produced the stream
+    /// * `database` - The database name
+    pub fn record_dispatch_duration(
+        &self,
+        duration: Duration,
+        operation: Option<&str>,
+        database: &str,
+    ) {
+        let attributes = self.operation_attributes(operation, database);
+        self.query_dispatch_duration
+            .record(duration.as_secs_f64() * 1000.0, &attributes);
+    }
+
+    /// Record how long a result stream took to be consumed, from creation to exhaustion
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - Time from stream creation until it returned its last row
+    /// * `operation` - The operation that produced the stream
+    /// * `database` - The database name
+    pub fn record_stream_duration(
+        &self,
+        duration: Duration,
+        operation: Option<&str>,
+        database: &str,
+    ) {
+        let attributes = self.operation_attributes(operation, database);
+        self.query_stream_duration
+            .record(duration.as_secs_f64() * 1000.0, &attributes);
+    }
+
+    /// Record the throughput of a fully drained result stream
+    ///
+    /// Nothing is recorded for empty or instantaneous streams, since no meaningful
+    /// rate can be derived from them.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The number of rows consumed from the stream
+    /// * `duration` - The time spent consuming the stream
+    /// * `operation` - The operation that produced the stream
+    /// * `database` - The database name
+    pub fn record_stream_throughput(
+        &self,
+        rows: u64,
+        duration: Duration,
+        operation: Option<&str>,
+        database: &str,
+    ) {
+        let Some(rate) = rows_per_second(rows, duration) else {
+            return;
+        };
+
+        self.query_rows_per_second
+            .record(rate, &self.operation_attributes(operation, database));
+    }
+
     /// Record a transaction start
     ///
     /// # Arguments
     ///
     /// * `database` - The database name
     pub fn record_transaction_start(&self, database: &str) {
-        let attributes = vec![KeyValue::new("database", database.to_string())];
+        let attributes = self.with_static(vec![self.database_attribute(database)]);
 
         self.transactions_total.add(1, &attributes);
+        self.totals.transactions.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Record a transaction completion
@@ -145,50 +661,332 @@ impl Neo4jMetrics {
     /// * `committed` - Whether the transaction was committed (true) or rolled back (false)
     /// * `database` - The database name
     pub fn record_transaction_end(&self, duration: Duration, committed: bool, database: &str) {
-        let attributes = vec![
-            KeyValue::new("database", database.to_string()),
-            KeyValue::new("outcome", if committed { "commit" } else { "rollback" }),
-        ];
+        let outcome = if committed { "commit" } else { "rollback" };
+        self.record_transaction_outcome(duration, outcome, database);
+    }
+
+    /// Record how many queries a transaction ran before it was committed or rolled back
+    ///
+    /// # Arguments
+    ///
+    /// * `queries` - The number of queries run in the transaction
+    /// * `committed` - Whether the transaction was committed (true) or rolled back (false)
+    /// * `database` - The database name
+    pub fn record_transaction_queries(&self, queries: u64, committed: bool, database: &str) {
+        let outcome = if committed { "commit" } else { "rollback" };
+        self.record_transaction_query_count(queries, outcome, database);
+    }
+
+    /// Record how many queries a transaction ran before it was dropped without an explicit
+    /// commit or rollback, with `outcome = "dropped"`
+    ///
+    /// # Arguments
+    ///
+    /// * `queries` - The number of queries run in the transaction
+    /// * `database` - The database name
+    pub fn record_transaction_queries_dropped(&self, queries: u64, database: &str) {
+        self.record_transaction_query_count(queries, "dropped", database);
+    }
+
+    fn record_transaction_query_count(&self, queries: u64, outcome: &'static str, database: &str) {
+        let attributes = self.with_static(vec![
+            self.database_attribute(database),
+            KeyValue::new("outcome", outcome),
+        ]);
+        self.transaction_queries.record(queries, &attributes);
+    }
+
+    /// Record a transaction dropped without an explicit commit or rollback
+    ///
+    /// The server rolls such a transaction back, so it counts as a rollback, but with
+    /// `outcome = "dropped"` to tell it apart from deliberate ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - Time from the transaction start until it was dropped
+    /// * `database` - The database name
+    pub fn record_transaction_dropped(&self, duration: Duration, database: &str) {
+        self.record_transaction_outcome(duration, "dropped", database);
+    }
+
+    fn record_transaction_outcome(
+        &self,
+        duration: Duration,
+        outcome: &'static str,
+        database: &str,
+    ) {
+        let attributes = self.with_static(vec![
+            self.database_attribute(database),
+            KeyValue::new("outcome", outcome),
+        ]);
 
         // Convert duration to milliseconds safely
         let millis = duration.as_secs_f64() * 1000.0;
-        self.transaction_duration.record(millis, &attributes);
+        in_span_context(|| self.transaction_duration.record(millis, &attributes));
 
-        if committed {
+        if outcome == "commit" {
             self.transaction_commits.add(1, &attributes);
         } else {
             self.transaction_rollbacks.add(1, &attributes);
         }
     }
 
+    /// Record a failed connection attempt that is about to be retried
+    ///
+    /// # Arguments
+    ///
+    /// * `error_type` - The [`classify_error`] value of the error the attempt failed with
+    pub fn record_connect_retry(&self, error_type: &str) {
+        let attributes =
+            self.with_static(vec![KeyValue::new("error_type", error_type.to_string())]);
+        self.connect_retries.add(1, &attributes);
+    }
+
+    /// Record a failed query attempt that is about to be retried
+    ///
+    /// # Arguments
+    ///
+    /// * `error_type` - The [`classify_error`] value of the error the attempt failed with
+    /// * `operation` - The operation being retried
+    /// * `database` - The database name
+    pub fn record_query_retry(&self, error_type: &str, operation: Option<&str>, database: &str) {
+        let mut attributes = self.operation_attributes(operation, database);
+        attributes.push(KeyValue::new("error_type", error_type.to_string()));
+        self.query_retries.add(1, &attributes);
+    }
+
     /// Increment the active connections counter
     pub fn increment_connections(&self) {
-        self.active_connections.add(1, &[]);
+        self.active_connections.add(1, &self.attributes);
+        self.totals.connections.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Decrement the active connections counter
     pub fn decrement_connections(&self) {
-        self.active_connections.add(-1, &[]);
+        self.active_connections.add(-1, &self.attributes);
+        self.totals.connections.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Record how long a connection was open before it closed
+    ///
+    /// Called when the last clone of an [`InstrumentedGraph`](crate::InstrumentedGraph) with
+    /// metrics enabled is dropped.
+    pub fn record_connection_duration(&self, lifetime: Duration) {
+        let millis = lifetime.as_secs_f64() * 1000.0;
+        self.connection_duration.record(millis, &self.attributes);
     }
 
     /// Record an error
     ///
+    /// The data point is labelled like a failed query, `success = false` included, plus
+    /// `error_type`.
+    ///
     /// # Arguments
     ///
     /// * `error_type` - The type/category of the error
     /// * `operation` - The operation that caused the error
     /// * `database` - The database name
     pub fn record_error(&self, error_type: &str, operation: Option<&str>, database: &str) {
+        self.record_error_as(error_type, operation, database, None);
+    }
+
+    /// [`record_error`](Self::record_error), labelling the data point with the statement's
+    /// `access_mode` when it is known
+    pub(crate) fn record_error_as(
+        &self,
+        error_type: &str,
+        operation: Option<&str>,
+        database: &str,
+        access_mode: Option<AccessMode>,
+    ) {
+        let mut attributes = self.query_attributes(false, operation, database, access_mode);
+        attributes.push(KeyValue::new("error_type", error_type.to_string()));
+
+        self.errors_total.add(1, &attributes);
+        self.totals.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Read the cumulative totals this instance (and its clones) have recorded
+    ///
+    /// The OpenTelemetry instruments remain the source of truth for exported metrics and full
+    /// histograms; the snapshot is a cheap read of plain counters for logging, tests and
+    /// health endpoints that don't have a metrics reader wired up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use opentelemetry::global;
+    /// use otel_instrumentation_neo4jrs::Neo4jMetrics;
+    ///
+    /// let metrics = Neo4jMetrics::new(&global::meter("neo4j"));
+    /// let snapshot = metrics.snapshot();
+    /// let healthz = format!(
+    ///     r#"{{"queries":{},"errors":{},"connections":{}}}"#,
+    ///     snapshot.queries_total, snapshot.errors_total, snapshot.active_connections
+    /// );
+    /// ```
+    #[must_use]
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            queries_total: self.totals.queries.load(Ordering::Relaxed),
+            errors_total: self.totals.errors.load(Ordering::Relaxed),
+            transactions_total: self.totals.transactions.load(Ordering::Relaxed),
+            active_connections: self.totals.connections.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Count the query in `neo4j.queries.slow` if it exceeded the slow query threshold
+    fn record_if_slow(&self, duration: Duration, operation: Option<&str>, database: &str) {
+        if self.slow_query_threshold.is_some_and(|t| duration > t) {
+            self.slow_queries
+                .add(1, &self.operation_attributes(operation, database));
+        }
+    }
+
+    /// `database` attribute for `database`, collapsed to [`OTHER_DATABASE`] if not allowed
+    fn database_attribute(&self, database: &str) -> KeyValue {
+        let value = match &self.database_allowlist {
+            Some(allowed) if !allowed.contains(database) => OTHER_DATABASE,
+            _ => database,
+        };
+        KeyValue::new("database", value.to_string())
+    }
+
+    fn query_attributes(
+        &self,
+        success: bool,
+        operation: Option<&str>,
+        database: &str,
+        access_mode: Option<AccessMode>,
+    ) -> Vec<KeyValue> {
         let mut attributes = vec![
-            KeyValue::new("error_type", error_type.to_string()),
-            KeyValue::new("database", database.to_string()),
+            KeyValue::new("success", success),
+            self.database_attribute(database),
         ];
 
         if let Some(op) = operation {
             attributes.push(KeyValue::new("operation", op.to_string()));
         }
+        if let Some(mode) = access_mode {
+            attributes.push(KeyValue::new("access_mode", mode.as_str()));
+        }
 
-        self.errors_total.add(1, &attributes);
+        self.with_static(attributes)
+    }
+
+    /// `database` and optional `operation` attributes for instruments without a `success` label
+    fn operation_attributes(&self, operation: Option<&str>, database: &str) -> Vec<KeyValue> {
+        let mut attributes = vec![self.database_attribute(database)];
+
+        if let Some(op) = operation {
+            attributes.push(KeyValue::new("operation", op.to_string()));
+        }
+
+        self.with_static(attributes)
+    }
+
+    /// `attributes` followed by the static attributes configured for every data point
+    fn with_static(&self, mut attributes: Vec<KeyValue>) -> Vec<KeyValue> {
+        attributes.extend(self.attributes.iter().cloned());
+        attributes
+    }
+}
+
+/// Compute rows per second, or `None` when the stream was empty or took no measurable time
+#[allow(clippy::cast_precision_loss)]
+fn rows_per_second(rows: u64, duration: Duration) -> Option<f64> {
+    let secs = duration.as_secs_f64();
+    if rows == 0 || secs <= 0.0 {
+        return None;
+    }
+    Some(rows as f64 / secs)
+}
+
+/// Approximate size of a row's values in bytes
+///
+/// neo4rs does not expose the Bolt message a row was decoded from, so this sums the length
+/// of every string, byte array and map key plus a fixed width per scalar (8 bytes for
+/// numbers, 1 for booleans and nulls), walking into lists, maps, nodes and relationships.
+/// It tracks the wire size closely enough to spot queries returning huge payloads.
+#[must_use]
+pub fn approximate_row_bytes(row: &Row) -> u64 {
+    row.to_strict::<ApproximateSize>().map_or(0, |size| size.0)
+}
+
+/// Deserializes any value into its approximate size, without allocating
+struct ApproximateSize(u64);
+
+impl<'de> Deserialize<'de> for ApproximateSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ApproximateSizeVisitor)
+    }
+}
+
+struct ApproximateSizeVisitor;
+
+impl<'de> Visitor<'de> for ApproximateSizeVisitor {
+    type Value = ApproximateSize;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any Bolt value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(ApproximateSize(1))
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(ApproximateSize(8))
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(ApproximateSize(8))
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(ApproximateSize(8))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(ApproximateSize(value.len() as u64))
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E> {
+        Ok(ApproximateSize(value.len() as u64))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(ApproximateSize(1))
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(ApproximateSize(1))
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        ApproximateSize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut total = 0;
+        while let Some(ApproximateSize(size)) = seq.next_element()? {
+            total += size;
+        }
+        Ok(ApproximateSize(total))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut total = 0;
+        while let Some((ApproximateSize(key), ApproximateSize(value))) = map.next_entry()? {
+            total += key + value;
+        }
+        Ok(ApproximateSize(total))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let (ApproximateSize(tag), variant) = data.variant()?;
+        let ApproximateSize(value) = variant.newtype_variant()?;
+        Ok(ApproximateSize(tag + value))
     }
 }
 
@@ -196,6 +994,12 @@ impl Neo4jMetrics {
 pub struct MetricsBuilder {
     meter: Option<Meter>,
     enabled: bool,
+    pool_stats: Option<Arc<dyn PoolStatsProvider>>,
+    duration_boundaries: Vec<f64>,
+    attributes: Vec<KeyValue>,
+    slow_query_threshold: Option<Duration>,
+    naming: NamingConvention,
+    database_allowlist: Option<HashSet<String>>,
 }
 
 impl MetricsBuilder {
@@ -205,6 +1009,12 @@ impl MetricsBuilder {
         Self {
             meter: None,
             enabled: false,
+            pool_stats: None,
+            duration_boundaries: DEFAULT_DURATION_BOUNDARIES.to_vec(),
+            attributes: Vec::new(),
+            slow_query_threshold: None,
+            naming: NamingConvention::default(),
+            database_allowlist: None,
         }
     }
 
@@ -220,16 +1030,127 @@ impl MetricsBuilder {
         self
     }
 
+    /// Report `neo4j.pool.in_use` and `neo4j.pool.idle` from `provider`
+    ///
+    /// Without a provider these gauges report zero.
+    #[must_use]
+    pub fn with_pool_stats(mut self, provider: Arc<dyn PoolStatsProvider>) -> Self {
+        self.pool_stats = Some(provider);
+        self
+    }
+
+    /// Bucket boundaries, in milliseconds, for the query and transaction duration histograms
+    ///
+    /// Defaults to [`DEFAULT_DURATION_BOUNDARIES`]; the SDK's own defaults are spaced for
+    /// seconds-scale values and put most database calls into the first bucket or two.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use otel_instrumentation_neo4jrs::metrics::MetricsBuilder;
+    ///
+    /// let builder = MetricsBuilder::new().with_duration_boundaries(vec![1.0, 10.0, 100.0]);
+    /// ```
+    #[must_use]
+    pub fn with_duration_boundaries(mut self, boundaries: Vec<f64>) -> Self {
+        self.duration_boundaries = boundaries;
+        self
+    }
+
+    /// Add `key = value` to every data point of every instrument
+    ///
+    /// Each distinct value creates a separate time series for every instrument, so only use
+    /// low-cardinality keys such as `deployment.environment` or a tenant id from a small,
+    /// fixed set; never request, user or other per-call values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use otel_instrumentation_neo4jrs::metrics::MetricsBuilder;
+    ///
+    /// let builder = MetricsBuilder::new()
+    ///     .with_attribute("deployment.environment", "production")
+    ///     .with_attribute("tenant.id", "acme");
+    /// ```
+    #[must_use]
+    pub fn with_attribute(
+        mut self,
+        key: impl Into<opentelemetry::Key>,
+        value: impl Into<opentelemetry::Value>,
+    ) -> Self {
+        self.attributes.push(KeyValue::new(key, value));
+        self
+    }
+
+    /// Count queries taking longer than `threshold` in `neo4j.queries.slow`
+    ///
+    /// The counter is tagged with `operation` and `database`; without a threshold it stays
+    /// at zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use otel_instrumentation_neo4jrs::metrics::MetricsBuilder;
+    /// use std::time::Duration;
+    ///
+    /// let builder = MetricsBuilder::new().with_slow_query_threshold(Duration::from_millis(500));
+    /// ```
+    #[must_use]
+    pub fn with_slow_query_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_query_threshold = Some(threshold);
+        self
+    }
+
+    /// Register instruments under `naming`'s spelling of their names
+    ///
+    /// Defaults to [`NamingConvention::Otlp`]. [`NamingConvention::Prometheus`] saves
+    /// renaming the instruments with views or in the collector when they end up in Prometheus.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use otel_instrumentation_neo4jrs::metrics::{MetricsBuilder, NamingConvention};
+    ///
+    /// let builder = MetricsBuilder::new().with_naming(NamingConvention::Prometheus);
+    /// ```
+    #[must_use]
+    pub fn with_naming(mut self, naming: NamingConvention) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Record only the databases in `databases` by name
+    ///
+    /// Every other database is recorded as `database="_other"` ([`OTHER_DATABASE`]), so
+    /// `run_on` calls against many or dynamically named databases can't grow the number of
+    /// time series without bound. Without an allowlist every database is recorded by name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use otel_instrumentation_neo4jrs::metrics::MetricsBuilder;
+    /// use std::collections::HashSet;
+    ///
+    /// let builder = MetricsBuilder::new()
+    ///     .with_database_allowlist(HashSet::from(["neo4j".to_string(), "movies".to_string()]));
+    /// ```
+    #[must_use]
+    pub fn with_database_allowlist(mut self, databases: HashSet<String>) -> Self {
+        self.database_allowlist = Some(databases);
+        self
+    }
+
     /// Build the metrics instance
     ///
     /// Returns `None` if metrics are not enabled
     #[must_use]
     pub fn build(self) -> Option<Arc<Neo4jMetrics>> {
-        if self.enabled {
-            self.meter.as_ref().map(Neo4jMetrics::new).map(Arc::new)
-        } else {
-            None
+        if !self.enabled {
+            return None;
         }
+        self.meter
+            .as_ref()
+            .map(|meter| Arc::new(Neo4jMetrics::with_options(meter, &self)))
     }
 }
 
@@ -239,7 +1160,55 @@ impl Default for MetricsBuilder {
     }
 }
 
+/// Run `f` with the current `tracing` span's OpenTelemetry context attached
+///
+/// `tracing-opentelemetry` doesn't make an entered span the active OpenTelemetry context,
+/// and the SDK samples histogram exemplars from the active context, so duration
+/// measurements are recorded inside this to carry the query's trace and span IDs.
+fn in_span_context<R>(f: impl FnOnce() -> R) -> R {
+    let _context = tracing::Span::current().context().attach();
+    f()
+}
+
+/// A measured operation duration that displays as fractional milliseconds, e.g. `12.345ms`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RecordedDuration(Duration);
+
+impl RecordedDuration {
+    /// The measured duration
+    #[must_use]
+    pub fn as_duration(self) -> Duration {
+        self.0
+    }
+
+    /// The measured duration in fractional milliseconds
+    #[must_use]
+    pub fn as_millis_f64(self) -> f64 {
+        self.0.as_secs_f64() * 1000.0
+    }
+}
+
+impl From<Duration> for RecordedDuration {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<RecordedDuration> for Duration {
+    fn from(recorded: RecordedDuration) -> Self {
+        recorded.0
+    }
+}
+
+impl fmt::Display for RecordedDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.3}ms", self.as_millis_f64())
+    }
+}
+
 /// Timer utility for measuring operation durations
+///
+/// Displays the time elapsed so far, like [`RecordedDuration`].
 pub struct OperationTimer {
     start: std::time::Instant,
 }
@@ -259,6 +1228,30 @@ impl OperationTimer {
         self.start.elapsed()
     }
 
+    /// Get the elapsed duration in fractional milliseconds
+    #[must_use]
+    pub fn elapsed_millis(&self) -> f64 {
+        RecordedDuration(self.elapsed()).as_millis_f64()
+    }
+
+    /// Stop the timer and return the elapsed duration, formatted in milliseconds by `{}`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use otel_instrumentation_neo4jrs::metrics::OperationTimer;
+    /// use std::time::Duration;
+    ///
+    /// let timer = OperationTimer::start();
+    /// let took = timer.stop();
+    /// println!("query took {took}"); // e.g. "query took 0.012ms"
+    /// let duration: Duration = took.into();
+    /// ```
+    #[must_use]
+    pub fn stop(self) -> RecordedDuration {
+        RecordedDuration(self.elapsed())
+    }
+
     /// Record the elapsed time to metrics and return the duration
     ///
     /// # Arguments
@@ -279,12 +1272,55 @@ impl OperationTimer {
         metrics.record_query(duration, success, operation, database);
         duration
     }
+
+    /// Record the elapsed time for an operation's result and return the duration
+    ///
+    /// Failed results are recorded through [`Neo4jMetrics::record_query_failure`] so the
+    /// error count carries the error's category and class.
+    #[must_use]
+    pub fn record_result<T>(
+        self,
+        metrics: &Neo4jMetrics,
+        result: &Result<T, neo4rs::Error>,
+        operation: Option<&str>,
+        database: &str,
+    ) -> Duration {
+        self.record_statement_result(metrics, result, operation, database, None)
+    }
+
+    /// [`record_result`](Self::record_result) for a statement of the given `access_mode`
+    pub(crate) fn record_statement_result<T>(
+        self,
+        metrics: &Neo4jMetrics,
+        result: &Result<T, neo4rs::Error>,
+        operation: Option<&str>,
+        database: &str,
+        access_mode: Option<AccessMode>,
+    ) -> Duration {
+        let duration = self.elapsed();
+        match result {
+            Ok(_) => metrics.record_query_as(duration, true, operation, database, access_mode),
+            Err(e) => {
+                metrics.record_query_failure_as(duration, e, operation, database, access_mode);
+            }
+        }
+        duration
+    }
+}
+
+impl fmt::Display for OperationTimer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        RecordedDuration(self.elapsed()).fmt(f)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{histogram_points, sum_points, CapturedMetrics};
     use opentelemetry::metrics::MeterProvider as _;
+    use opentelemetry::Value;
+    use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData};
     use opentelemetry_sdk::metrics::SdkMeterProvider;
 
     #[test]
@@ -300,6 +1336,459 @@ mod tests {
         metrics.increment_connections();
         metrics.decrement_connections();
         metrics.record_error("connection", Some("MATCH"), "neo4j");
+        metrics.record_stream_throughput(500, Duration::from_millis(250), Some("execute"), "neo4j");
+        metrics.record_returned_rows(500, Some("execute"), "neo4j");
+        metrics.record_connect_retry("connection");
+        metrics.record_dispatch_duration(Duration::from_millis(3), Some("execute"), "neo4j");
+        metrics.record_stream_duration(Duration::from_millis(40), Some("execute"), "neo4j");
+    }
+
+    #[test]
+    fn test_snapshot_tracks_totals() {
+        let provider = SdkMeterProvider::default();
+        let meter = provider.meter("test");
+        let metrics = Neo4jMetrics::new(&meter);
+        let shared = metrics.clone();
+
+        assert_eq!(metrics.snapshot(), MetricsSnapshot::default());
+
+        metrics.record_query(Duration::from_millis(5), true, Some("run"), "neo4j");
+        shared.record_query(Duration::from_millis(5), false, Some("run"), "neo4j");
+        metrics.record_transaction_start("neo4j");
+        metrics.record_error("connection", None, "neo4j");
+        metrics.increment_connections();
+        shared.increment_connections();
+        metrics.decrement_connections();
+
+        assert_eq!(
+            shared.snapshot(),
+            MetricsSnapshot {
+                queries_total: 2,
+                errors_total: 2,
+                transactions_total: 1,
+                active_connections: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_query_failure_labels_error_class() {
+        let captured = CapturedMetrics::install();
+        captured.metrics.record_query_failure(
+            Duration::from_millis(5),
+            &std::io::Error::from(std::io::ErrorKind::ConnectionRefused).into(),
+            Some("run"),
+            "neo4j",
+        );
+
+        let points = sum_points(&captured, "neo4j.errors.total");
+        let (attributes, value) = &points[0];
+        assert_eq!(*value, 1);
+        assert!(attributes.contains(&KeyValue::new("error_class", "DriverError")));
+        assert!(attributes.contains(&KeyValue::new("error_type", "connection_refused")));
+        assert_eq!(captured.metrics.snapshot().errors_total, 1);
+    }
+
+    #[test]
+    fn test_error_labels_match_query_labels() {
+        let captured = CapturedMetrics::install();
+        let metrics = &captured.metrics;
+        let error: neo4rs::Error =
+            std::io::Error::from(std::io::ErrorKind::ConnectionRefused).into();
+        let duration = Duration::from_millis(5);
+
+        let read = Some(AccessMode::Read);
+
+        metrics.record_query(duration, true, Some("run"), "neo4j");
+        metrics.record_query(duration, false, Some("run"), "neo4j");
+        metrics.record_query_failure(duration, &error, Some("run"), "neo4j");
+        metrics.record_failure(&error, Some("execute"), "neo4j", None);
+        metrics.record_error("connection", Some("run"), "neo4j");
+        metrics.record_query_as(duration, false, Some("MATCH"), "neo4j", read);
+        metrics.record_failure(&error, Some("MATCH"), "neo4j", read);
+        metrics.record_error_as("connection", Some("MATCH"), "neo4j", read);
+
+        let keys = |name: &str| -> Vec<Vec<String>> {
+            sum_points(&captured, name)
+                .into_iter()
+                .map(|(attributes, _)| {
+                    let mut keys: Vec<String> = attributes
+                        .iter()
+                        .map(|kv| kv.key.to_string())
+                        .filter(|key| key != "error_type" && key != "error_class")
+                        .collect();
+                    keys.sort();
+                    keys
+                })
+                .collect()
+        };
+
+        // The error labels on top of a failed query's are the only difference
+        let query_keys = keys("neo4j.queries.total");
+        let error_keys = keys("neo4j.errors.total");
+        assert_eq!(error_keys.len(), 7);
+        for keys in &error_keys {
+            assert!(
+                query_keys.contains(keys),
+                "{keys:?} not among {query_keys:?}"
+            );
+        }
+        let with_access_mode = error_keys
+            .iter()
+            .filter(|keys| keys.iter().any(|key| key == "access_mode"))
+            .count();
+        assert_eq!(with_access_mode, 3);
+    }
+
+    struct FixedPool;
+
+    impl PoolStatsProvider for FixedPool {
+        fn pool_stats(&self) -> PoolStats {
+            PoolStats { in_use: 3, idle: 7 }
+        }
+    }
+
+    fn gauge_value(captured: &CapturedMetrics, name: &str) -> i64 {
+        captured.inspect(name, |data| {
+            let AggregatedMetrics::I64(MetricData::Gauge(gauge)) = data else {
+                panic!("unexpected {name} type");
+            };
+            gauge
+                .data_points()
+                .next()
+                .expect("no gauge data point")
+                .value()
+        })
+    }
+
+    #[test]
+    fn test_pool_gauges_read_provider() {
+        let captured = CapturedMetrics::install_with(|meter| {
+            Neo4jMetrics::with_pool_stats(meter, Some(Arc::new(FixedPool)))
+        });
+
+        assert_eq!(gauge_value(&captured, "neo4j.pool.in_use"), 3);
+        assert_eq!(gauge_value(&captured, "neo4j.pool.idle"), 7);
+    }
+
+    #[test]
+    fn test_pool_gauges_report_zero_without_provider() {
+        let captured = CapturedMetrics::install();
+
+        assert_eq!(gauge_value(&captured, "neo4j.pool.in_use"), 0);
+        assert_eq!(gauge_value(&captured, "neo4j.pool.idle"), 0);
+    }
+
+    #[test]
+    fn test_query_retries_labelled_with_error_type() {
+        let captured = CapturedMetrics::install();
+        captured
+            .metrics
+            .record_query_retry("transient", Some("run"), "default");
+        captured
+            .metrics
+            .record_query_retry("transient", Some("run"), "default");
+
+        let points = sum_points(&captured, "neo4j.query.retries");
+        let (attributes, value) = &points[0];
+        assert_eq!(*value, 2);
+        assert!(attributes.contains(&KeyValue::new("error_type", "transient")));
+    }
+
+    fn duration_bounds(captured: &CapturedMetrics, name: &str) -> Vec<f64> {
+        histogram_points::<f64>(captured, name)
+            .swap_remove(0)
+            .bounds
+    }
+
+    #[test]
+    fn test_histograms_declare_units() {
+        let captured = CapturedMetrics::install();
+        let metrics = &captured.metrics;
+        metrics.record_query(Duration::from_millis(3), true, None, "default");
+        metrics.record_transaction_end(Duration::from_millis(3), true, "default");
+        metrics.record_connection_duration(Duration::from_secs(3));
+        metrics.record_dispatch_duration(Duration::from_millis(3), None, "default");
+        metrics.record_stream_duration(Duration::from_millis(3), None, "default");
+        metrics.record_response_bytes(512, None, "default");
+
+        for name in [
+            "neo4j.query.duration",
+            "neo4j.transaction.duration",
+            "neo4j.connection.duration",
+            "neo4j.query.dispatch.duration",
+            "neo4j.query.stream.duration",
+        ] {
+            assert_eq!(captured.unit(name), "ms", "{name}");
+        }
+        assert_eq!(captured.unit("neo4j.query.response.bytes"), "By");
+    }
+
+    #[test]
+    fn test_duration_histograms_use_default_boundaries() {
+        let captured = CapturedMetrics::install();
+        captured
+            .metrics
+            .record_query(Duration::from_millis(3), true, None, "default");
+        captured
+            .metrics
+            .record_transaction_end(Duration::from_millis(12), true, "default");
+
+        assert_eq!(
+            duration_bounds(&captured, "neo4j.query.duration"),
+            DEFAULT_DURATION_BOUNDARIES
+        );
+        assert_eq!(
+            duration_bounds(&captured, "neo4j.transaction.duration"),
+            DEFAULT_DURATION_BOUNDARIES
+        );
+    }
+
+    #[test]
+    fn test_duration_boundaries_are_configurable() {
+        let captured = CapturedMetrics::install_configured(|builder| {
+            builder.with_duration_boundaries(vec![1.0, 10.0, 100.0])
+        });
+        captured
+            .metrics
+            .record_query(Duration::from_millis(3), true, None, "default");
+
+        assert_eq!(
+            duration_bounds(&captured, "neo4j.query.duration"),
+            [1.0, 10.0, 100.0]
+        );
+    }
+
+    #[test]
+    fn test_static_attributes_added_to_every_data_point() {
+        let captured = CapturedMetrics::install_configured(|builder| {
+            builder
+                .with_duration_boundaries(vec![1.0])
+                .with_attribute("tenant.id", "acme")
+        });
+        captured
+            .metrics
+            .record_query(Duration::from_millis(3), true, Some("MATCH"), "default");
+        captured.metrics.increment_connections();
+
+        let points = sum_points(&captured, "neo4j.queries.total");
+        let (attributes, _) = &points[0];
+        assert!(attributes.contains(&KeyValue::new("tenant.id", "acme")));
+        assert!(attributes.iter().any(|kv| kv.key.as_str() == "operation"));
+        captured.inspect("neo4j.connections.active", |data| {
+            let AggregatedMetrics::I64(MetricData::Sum(sum)) = data else {
+                panic!("unexpected connections counter type");
+            };
+            let point = sum.data_points().next().expect("no connections data point");
+            assert!(point.attributes().any(|kv| kv.key.as_str() == "tenant.id"));
+        });
+    }
+
+    #[test]
+    fn test_slow_queries_counted_past_threshold() {
+        let captured = CapturedMetrics::install_configured(|builder| {
+            builder
+                .with_duration_boundaries(vec![1.0])
+                .with_slow_query_threshold(Duration::from_millis(100))
+        });
+        captured
+            .metrics
+            .record_query(Duration::from_millis(50), true, Some("MATCH"), "default");
+        captured
+            .metrics
+            .record_query(Duration::from_millis(150), true, Some("MATCH"), "default");
+        captured.metrics.record_query_failure(
+            Duration::from_millis(200),
+            &neo4rs::Error::ConnectionError,
+            Some("MATCH"),
+            "default",
+        );
+
+        let points = sum_points(&captured, "neo4j.queries.slow");
+        let (attributes, value) = &points[0];
+        assert_eq!(*value, 2);
+        assert!(attributes.contains(&KeyValue::new("operation", "MATCH")));
+        assert!(attributes.contains(&KeyValue::new("database", "default")));
+    }
+
+    #[test]
+    fn test_prometheus_naming() {
+        let captured = CapturedMetrics::install_configured(|builder| {
+            builder
+                .with_duration_boundaries(vec![1.0])
+                .with_naming(NamingConvention::Prometheus)
+        });
+        captured
+            .metrics
+            .record_query(Duration::from_millis(3), true, None, "default");
+        captured
+            .metrics
+            .record_transaction_end(Duration::from_millis(12), true, "default");
+
+        for name in [
+            "neo4j_queries_total",
+            "neo4j_query_duration_milliseconds",
+            "neo4j_transaction_commits_total",
+            "neo4j_transaction_duration_milliseconds",
+        ] {
+            captured.inspect(name, |_| ());
+        }
+        assert!(captured
+            .try_inspect("neo4j.queries.total", |_| ())
+            .is_none());
+    }
+
+    #[test]
+    fn test_naming_convention_names() {
+        let prometheus = NamingConvention::Prometheus;
+        assert_eq!(
+            prometheus.counter("neo4j.queries.total"),
+            "neo4j_queries_total"
+        );
+        assert_eq!(
+            prometheus.counter("neo4j.query.retries"),
+            "neo4j_query_retries_total"
+        );
+        assert_eq!(prometheus.name("neo4j.pool.in_use"), "neo4j_pool_in_use");
+        assert_eq!(
+            NamingConvention::Otlp.duration("neo4j.query.duration"),
+            "neo4j.query.duration"
+        );
+    }
+
+    #[test]
+    fn test_databases_outside_allowlist_recorded_as_other() {
+        let captured = CapturedMetrics::install_configured(|builder| {
+            builder
+                .with_duration_boundaries(vec![1.0])
+                .with_database_allowlist(HashSet::from(["movies".to_string()]))
+        });
+        for database in ["movies", "tenant_1", "tenant_2"] {
+            captured
+                .metrics
+                .record_query(Duration::from_millis(3), true, None, database);
+            captured.metrics.record_transaction_start(database);
+        }
+
+        for name in ["neo4j.queries.total", "neo4j.transactions.total"] {
+            let mut databases: Vec<_> = sum_points(&captured, name)
+                .into_iter()
+                .map(|(attributes, value)| {
+                    let database = attributes
+                        .into_iter()
+                        .find(|kv| kv.key.as_str() == "database")
+                        .expect("no database attribute");
+                    (database.value.as_str().into_owned(), value)
+                })
+                .collect();
+            databases.sort();
+            assert_eq!(
+                databases,
+                [("_other".to_string(), 2), ("movies".to_string(), 1)]
+            );
+        }
+    }
+
+    #[test]
+    fn test_approximate_row_bytes() {
+        use neo4rs::{BoltList, BoltNull, BoltType};
+
+        let row = |value: BoltType| {
+            Row::new(
+                BoltList::from(vec!["v".into()]),
+                BoltList::from(vec![value]),
+            )
+        };
+        assert_eq!(approximate_row_bytes(&row("hello".into())), 6);
+        assert_eq!(approximate_row_bytes(&row(7_i64.into())), 9);
+        assert_eq!(approximate_row_bytes(&row(true.into())), 2);
+        assert_eq!(approximate_row_bytes(&row(BoltType::Null(BoltNull))), 2);
+        assert_eq!(approximate_row_bytes(&row(vec!["ab", "cd"].into())), 5);
+        assert_eq!(
+            approximate_row_bytes(&Row::new(BoltList::new(), BoltList::new())),
+            0
+        );
+    }
+
+    #[test]
+    fn test_queries_labelled_with_access_mode() {
+        let captured = CapturedMetrics::install();
+        captured.metrics.record_query_as(
+            Duration::from_millis(5),
+            true,
+            Some("run"),
+            "default",
+            Some(AccessMode::Write),
+        );
+        captured
+            .metrics
+            .record_query(Duration::from_millis(5), true, Some("run"), "default");
+
+        let modes: Vec<_> = sum_points(&captured, "neo4j.queries.total")
+            .into_iter()
+            .map(|(attributes, _)| {
+                attributes
+                    .into_iter()
+                    .find(|kv| kv.key.as_str() == "access_mode")
+                    .map(|kv| kv.value.as_str().into_owned())
+            })
+            .collect();
+        assert_eq!(modes.len(), 2);
+        assert!(modes.contains(&Some("write".to_owned())));
+        assert!(modes.contains(&None));
+    }
+
+    #[test]
+    fn test_transaction_queries_recorded_per_outcome() {
+        let captured = CapturedMetrics::install_configured(|builder| {
+            builder.with_attribute("tenant.id", "acme")
+        });
+        captured
+            .metrics
+            .record_transaction_queries(12, true, "default");
+
+        let points = histogram_points::<u64>(&captured, "neo4j.transaction.queries");
+        assert_eq!(points[0].sum, 12);
+        assert_eq!(points[0].attribute("outcome"), Some(&Value::from("commit")));
+        assert_eq!(points[0].attribute("tenant.id"), Some(&Value::from("acme")));
+    }
+
+    #[test]
+    fn test_query_text_length_recorded_per_operation() {
+        let captured = CapturedMetrics::install();
+        captured
+            .metrics
+            .record_query_text_length(42, Some("MERGE Person"), "default");
+
+        let points = histogram_points::<u64>(&captured, "neo4j.query.text.length");
+        assert_eq!(points[0].sum, 42);
+        assert_eq!(
+            points[0].attribute("operation"),
+            Some(&Value::from("MERGE Person"))
+        );
+    }
+
+    #[test]
+    fn test_durations_recorded_in_span_context() {
+        use crate::test_support::CapturedSpans;
+        use opentelemetry::trace::{TraceContextExt, TraceId};
+        use opentelemetry::Context;
+
+        let spans = CapturedSpans::install();
+        let trace_id = tracing::info_span!("neo4j.query")
+            .in_scope(|| in_span_context(|| Context::current().span().span_context().trace_id()));
+
+        assert_ne!(trace_id, TraceId::INVALID);
+        assert_eq!(trace_id, spans.span("neo4j.query").span_context.trace_id());
+    }
+
+    #[test]
+    fn test_rows_per_second() {
+        let rate = rows_per_second(500, Duration::from_millis(250)).unwrap();
+        assert!((rate - 2000.0).abs() < f64::EPSILON);
+
+        assert!(rows_per_second(0, Duration::from_secs(1)).is_none());
+        assert!(rows_per_second(10, Duration::ZERO).is_none());
     }
 
     #[test]
@@ -318,6 +1807,33 @@ mod tests {
         assert!(metrics.is_none());
     }
 
+    #[test]
+    fn test_recorded_duration_millis() {
+        let recorded = RecordedDuration::from(Duration::from_micros(1500));
+        assert!((recorded.as_millis_f64() - 1.5).abs() < f64::EPSILON);
+        assert_eq!(recorded.to_string(), "1.500ms");
+        assert_eq!(Duration::from(recorded), Duration::from_micros(1500));
+
+        let recorded = RecordedDuration::from(Duration::from_secs(2));
+        assert!((recorded.as_millis_f64() - 2000.0).abs() < f64::EPSILON);
+        assert_eq!(
+            RecordedDuration::from(Duration::ZERO).to_string(),
+            "0.000ms"
+        );
+    }
+
+    #[test]
+    fn test_operation_timer_stop() {
+        let timer = OperationTimer::start();
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(timer.elapsed_millis() >= 5.0);
+        assert!(timer.to_string().ends_with("ms"));
+
+        let recorded = timer.stop();
+        assert!(recorded.as_duration() >= Duration::from_millis(5));
+        assert!(recorded.as_millis_f64() >= 5.0);
+    }
+
     #[test]
     fn test_operation_timer() {
         let provider = SdkMeterProvider::default();
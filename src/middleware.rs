@@ -0,0 +1,165 @@
+//! Middleware around query execution
+//!
+//! A [`QueryMiddleware`] sees every query run through
+//! [`InstrumentedGraph::run`](crate::InstrumentedGraph::run),
+//! [`InstrumentedGraph::execute`](crate::InstrumentedGraph::execute) and their variants
+//! before the driver does, inside the operation's span. It can add attributes to that span, hold the
+//! query back (e.g. for rate limiting), rewrite it, or short-circuit with an error, and
+//! passes it on by calling [`Next::run`].
+
+use futures::future::BoxFuture;
+use neo4rs::Query;
+use std::sync::Arc;
+
+/// Cross-cutting logic run around each instrumented query
+///
+/// Middleware installed with
+/// [`InstrumentedGraph::with_middleware`](crate::InstrumentedGraph::with_middleware) runs in
+/// installation order, the first one outermost. The operation span is the current span
+/// while it runs, and the time it takes counts towards the operation's duration.
+///
+/// # Example
+///
+/// ```rust
+/// use futures::future::BoxFuture;
+/// use futures::FutureExt;
+/// use neo4rs::Query;
+/// use otel_instrumentation_neo4jrs::middleware::{Next, QueryMiddleware};
+///
+/// /// Tags every query span with the tenant the service runs for
+/// struct TenantTag(&'static str);
+///
+/// impl QueryMiddleware for TenantTag {
+///     fn call<'a>(
+///         &'a self,
+///         query: Query,
+///         next: Next<'a>,
+///     ) -> BoxFuture<'a, Result<(), neo4rs::Error>> {
+///         tracing::Span::current().record("peer.service", self.0);
+///         next.run(query).boxed()
+///     }
+/// }
+/// ```
+pub trait QueryMiddleware: Send + Sync {
+    /// Handle `query`, passing it on with `next.run(query)` unless short-circuiting
+    fn call<'a>(&'a self, query: Query, next: Next<'a>)
+        -> BoxFuture<'a, Result<(), neo4rs::Error>>;
+}
+
+/// The rest of the middleware chain, ending in the driver call
+pub struct Next<'a> {
+    middleware: &'a [Arc<dyn QueryMiddleware>],
+    driver: &'a (dyn Fn(Query) -> BoxFuture<'a, Result<(), neo4rs::Error>> + Send + Sync),
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(
+        middleware: &'a [Arc<dyn QueryMiddleware>],
+        driver: &'a (dyn Fn(Query) -> BoxFuture<'a, Result<(), neo4rs::Error>> + Send + Sync),
+    ) -> Self {
+        Self { middleware, driver }
+    }
+
+    /// Pass `query` to the next middleware, or to the driver after the last one
+    ///
+    /// # Errors
+    ///
+    /// Returns whatever error the rest of the chain or the driver fails with
+    #[must_use]
+    pub fn run(self, query: Query) -> BoxFuture<'a, Result<(), neo4rs::Error>> {
+        match self.middleware.split_first() {
+            Some((first, rest)) => first.call(
+                query,
+                Next {
+                    middleware: rest,
+                    driver: self.driver,
+                },
+            ),
+            None => (self.driver)(query),
+        }
+    }
+}
+
+/// Middleware that rejects every query without reaching the driver, for tests
+#[cfg(test)]
+pub(crate) struct Reject;
+
+#[cfg(test)]
+impl QueryMiddleware for Reject {
+    fn call<'a>(&'a self, _: Query, _: Next<'a>) -> BoxFuture<'a, Result<(), neo4rs::Error>> {
+        Box::pin(async { Err(neo4rs::Error::InvalidConfig) })
+    }
+}
+
+/// Middleware that succeeds without passing the query on, for tests
+#[cfg(test)]
+pub(crate) struct Skip;
+
+#[cfg(test)]
+impl QueryMiddleware for Skip {
+    fn call<'a>(&'a self, _: Query, _: Next<'a>) -> BoxFuture<'a, Result<(), neo4rs::Error>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+    use std::sync::Mutex;
+
+    /// Records its name on the way in and out of the chain
+    struct Trace(&'static str, Arc<Mutex<Vec<String>>>);
+
+    impl QueryMiddleware for Trace {
+        fn call<'a>(
+            &'a self,
+            query: Query,
+            next: Next<'a>,
+        ) -> BoxFuture<'a, Result<(), neo4rs::Error>> {
+            async move {
+                self.1.lock().unwrap().push(format!("{} in", self.0));
+                let result = next.run(query).await;
+                self.1.lock().unwrap().push(format!("{} out", self.0));
+                result
+            }
+            .boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chain_runs_in_order_around_driver() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let middleware: Vec<Arc<dyn QueryMiddleware>> = vec![
+            Arc::new(Trace("outer", log.clone())),
+            Arc::new(Trace("inner", log.clone())),
+        ];
+        let driver_log = log.clone();
+        let driver = move |_: Query| {
+            driver_log.lock().unwrap().push("driver".to_owned());
+            async { Ok(()) }.boxed()
+        };
+
+        Next::new(&middleware, &driver)
+            .run(Query::new("RETURN 1".to_owned()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["outer in", "inner in", "driver", "inner out", "outer out"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_short_circuit_skips_driver() {
+        let middleware: Vec<Arc<dyn QueryMiddleware>> = vec![Arc::new(Reject)];
+        let driver = |_: Query| async { Ok(()) }.boxed();
+
+        let result = Next::new(&middleware, &driver)
+            .run(Query::new("RETURN 1".to_owned()))
+            .await;
+
+        assert!(matches!(result, Err(neo4rs::Error::InvalidConfig)));
+    }
+}
@@ -0,0 +1,1663 @@
+//! Query wrappers and inspection helpers
+//!
+//! `neo4rs::Query` keeps its text and parameters private, so nothing about a plain query can
+//! be recorded beyond the method it was passed to. [`TracedQuery`] keeps the Cypher text and
+//! parameters alongside the query so they can be recorded on spans, and [`AnnotatedQuery`]
+//! adds a trace comment and an explicit operation name on top of it.
+
+use crate::redact::render_parameters;
+use crate::telemetry::TelemetryConfig;
+use neo4rs::{BoltMap, BoltNull, BoltType, Query};
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::{global, Context};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// A Cypher query that remembers its text and parameters for instrumentation
+///
+/// Build it like a `neo4rs::Query` and pass it to
+/// [`InstrumentedGraph::run_traced_query`](crate::InstrumentedGraph::run_traced_query) to have
+/// `db.query.text` recorded on the span. The query sent to Neo4j is identical to one built
+/// with `neo4rs::query(...)` and the same parameters.
+///
+/// # Example
+///
+/// ```rust
+/// use otel_instrumentation_neo4jrs::query::TracedQuery;
+///
+/// let q = TracedQuery::new("MATCH (p:Person {name: $name}) RETURN p").param("name", "Alice");
+/// assert_eq!(q.text(), "MATCH (p:Person {name: $name}) RETURN p");
+/// ```
+#[derive(Clone, Debug)]
+pub struct TracedQuery {
+    text: String,
+    params: BoltMap,
+}
+
+impl TracedQuery {
+    /// Create a query from Cypher text
+    #[must_use]
+    pub fn new(cypher: &str) -> Self {
+        Self {
+            text: cypher.to_owned(),
+            params: BoltMap::default(),
+        }
+    }
+
+    /// Add a parameter, as with `neo4rs::Query::param`
+    #[must_use]
+    pub fn param<T: Into<BoltType>>(mut self, key: &str, value: T) -> Self {
+        self.params.put(key.into(), value.into());
+        self
+    }
+
+    /// The Cypher text this query was built from
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The parameters added so far
+    #[must_use]
+    pub fn params(&self) -> &BoltMap {
+        &self.params
+    }
+
+    /// Prepend `comment` to the Cypher sent to the server, see [`AnnotatedQuery`]
+    #[must_use]
+    pub fn with_trace_comment(self, comment: &str) -> AnnotatedQuery {
+        AnnotatedQuery::from(self).with_trace_comment(comment)
+    }
+
+    /// Name the operation span `name` instead of the parsed operation, see [`AnnotatedQuery`]
+    #[must_use]
+    pub fn with_operation_name(self, name: &str) -> AnnotatedQuery {
+        AnnotatedQuery::from(self).with_operation_name(name)
+    }
+
+    /// Bind the current trace context to the [`TRACEPARENT_PARAM`] parameter
+    ///
+    /// The context of the current `tracing` span is serialized as a W3C `traceparent` by
+    /// the global text map propagator, so install one first, e.g. `TraceContextPropagator`
+    /// from `opentelemetry_sdk` with `opentelemetry::global::set_text_map_propagator`. The
+    /// Cypher must reference `$__traceparent` to store it, for triggers or other services
+    /// that continue the trace. The parameter is `null` if the propagator produced no
+    /// `traceparent`, for instance outside of any sampled span.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use otel_instrumentation_neo4jrs::query::TracedQuery;
+    ///
+    /// let q = TracedQuery::new("CREATE (:Order {id: $id, traceparent: $__traceparent})")
+    ///     .param("id", 42)
+    ///     .with_trace_context();
+    /// assert_eq!(q.params().value.len(), 2);
+    /// ```
+    #[must_use]
+    pub fn with_trace_context(self) -> Self {
+        let cx = Span::current().context();
+        let traceparent =
+            global::get_text_map_propagator(|propagator| traceparent(propagator, &cx));
+        self.with_traceparent(traceparent)
+    }
+
+    fn with_traceparent(self, traceparent: Option<String>) -> Self {
+        match traceparent {
+            Some(traceparent) => self.param(TRACEPARENT_PARAM, traceparent),
+            None => self.param(TRACEPARENT_PARAM, BoltType::Null(BoltNull)),
+        }
+    }
+}
+
+/// Parameter [`TracedQuery::with_trace_context`] binds the W3C `traceparent` to
+pub const TRACEPARENT_PARAM: &str = "__traceparent";
+
+/// The `traceparent` header `propagator` writes for `cx`, if any
+fn traceparent(propagator: &dyn TextMapPropagator, cx: &Context) -> Option<String> {
+    let mut carrier = HashMap::new();
+    propagator.inject_context(cx, &mut carrier);
+    carrier.remove("traceparent")
+}
+
+impl From<TracedQuery> for Query {
+    fn from(q: TracedQuery) -> Self {
+        Query::new(q.text).params(q.params.value)
+    }
+}
+
+/// A [`TracedQuery`] with a trace comment and/or an explicit operation name
+///
+/// The comment is sent to the server as a leading `//` line, so it shows up in the query log
+/// and `SHOW TRANSACTIONS` and can tie a server-side query back to the trace that issued it.
+/// Line breaks in the comment are replaced by spaces so it cannot spill into the statement.
+///
+/// The operation name replaces the one parsed from the Cypher text as span name and
+/// `db.operation.name`. `db.query.text` records the statement without the comment.
+///
+/// # Example
+///
+/// ```rust
+/// use otel_instrumentation_neo4jrs::query::TracedQuery;
+///
+/// let q = TracedQuery::new("MATCH (p:Person) RETURN p")
+///     .with_trace_comment("traceparent=00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+///     .with_operation_name("list_people");
+/// assert_eq!(
+///     q.cypher(),
+///     "// traceparent=00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01\nMATCH (p:Person) RETURN p"
+/// );
+/// assert_eq!(q.operation_name(), Some("list_people"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct AnnotatedQuery {
+    query: TracedQuery,
+    comment: Option<String>,
+    operation_name: Option<String>,
+}
+
+impl AnnotatedQuery {
+    /// Prepend `comment` to the Cypher sent to the server, replacing any earlier comment
+    #[must_use]
+    pub fn with_trace_comment(mut self, comment: &str) -> Self {
+        self.comment = Some(comment.replace(['\r', '\n'], " "));
+        self
+    }
+
+    /// Name the operation span `name` instead of the operation parsed from the Cypher text
+    #[must_use]
+    pub fn with_operation_name(mut self, name: &str) -> Self {
+        self.operation_name = Some(name.to_owned());
+        self
+    }
+
+    /// The Cypher text sent to the server, including the comment line
+    #[must_use]
+    pub fn cypher(&self) -> String {
+        match &self.comment {
+            Some(comment) => format!("// {comment}\n{}", self.query.text()),
+            None => self.query.text().to_owned(),
+        }
+    }
+
+    /// The trace comment, if any
+    #[must_use]
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// The explicit operation name, if any
+    #[must_use]
+    pub fn operation_name(&self) -> Option<&str> {
+        self.operation_name.as_deref()
+    }
+
+    /// The wrapped query, without the comment
+    #[must_use]
+    pub fn query(&self) -> &TracedQuery {
+        &self.query
+    }
+}
+
+impl From<TracedQuery> for AnnotatedQuery {
+    fn from(query: TracedQuery) -> Self {
+        Self {
+            query,
+            comment: None,
+            operation_name: None,
+        }
+    }
+}
+
+impl From<AnnotatedQuery> for Query {
+    fn from(q: AnnotatedQuery) -> Self {
+        Query::new(q.cypher()).params(q.query.params.value)
+    }
+}
+
+/// Any query the instrumented graph can run: a plain `neo4rs::Query`, a [`TracedQuery`] or an
+/// [`AnnotatedQuery`]
+///
+/// Methods taking `impl Into<RunnableQuery>` accept all three; the more the query knows
+/// about itself, the more is recorded on the span.
+pub struct RunnableQuery(pub(crate) Statement);
+
+impl From<Query> for RunnableQuery {
+    fn from(q: Query) -> Self {
+        Self(Statement::Plain(q))
+    }
+}
+
+impl From<TracedQuery> for RunnableQuery {
+    fn from(q: TracedQuery) -> Self {
+        Self(Statement::Traced(q))
+    }
+}
+
+impl From<AnnotatedQuery> for RunnableQuery {
+    fn from(q: AnnotatedQuery) -> Self {
+        Self(Statement::Annotated(q))
+    }
+}
+
+/// A query handed to one of the instrumented operations
+///
+/// Lets the shared operation bodies accept plain, traced and annotated queries while
+/// recording whatever is known about the statement on the operation span.
+pub(crate) enum Statement {
+    Plain(Query),
+    Traced(TracedQuery),
+    Annotated(AnnotatedQuery),
+}
+
+impl Statement {
+    /// Record the operation name, statement text and parameter shapes on `span`
+    ///
+    /// Annotated queries with an operation name use it for the span. Other traced queries
+    /// name the span after their parsed operation (e.g. `MATCH Person`) if `config` allows
+    /// it; plain queries, and Cypher that cannot be parsed, fall back to `operation`. Traced
+    /// queries also record:
+    ///
+    /// - `db.query.text`, unless `config` holds it back for failures
+    /// - `db.query.summary`, the normalised statement, see [`summarize_query`]
+    /// - `neo4j.procedure.namespace` of `CALL`ed procedures, see [`procedure_namespace`]
+    /// - `neo4j.query.access_mode`, see [`access_mode`]
+    /// - `db.collection.name`, see [`primary_label`]
+    /// - `db.query.parameter_count`, always, and `db.query.parameter.<key>.size` of list and
+    ///   map parameters, see [`record_parameter_sizes`]
+    /// - `db.query.parameters`, only if `config` has a redactor installed
+    ///
+    /// Apart from the explicit name and the parameter count, none of these are built for
+    /// spans that are not sampled, unless `config` says otherwise.
+    pub(crate) fn record(&self, span: &Span, operation: &str, config: &TelemetryConfig) {
+        let (q, operation_name) = match self {
+            Self::Plain(_) => {
+                span.record("db.operation.name", operation);
+                return;
+            }
+            Self::Traced(q) => (q, None),
+            Self::Annotated(q) => (&q.query, q.operation_name()),
+        };
+
+        // The count and an explicit name are cheap to record, so they apply whether or not the
+        // span is sampled
+        span.record(
+            "db.query.parameter_count",
+            i64::try_from(q.params().len()).unwrap_or(i64::MAX),
+        );
+        if let Some(name) = operation_name {
+            span.record("db.operation.name", name);
+            span.record("otel.name", name);
+        }
+        if !config.should_record_statement(span) {
+            if operation_name.is_none() {
+                span.record("db.operation.name", operation);
+            }
+            return;
+        }
+
+        if operation_name.is_none() {
+            match parse_operation(q.text()).filter(|parsed| config.records_operation(parsed)) {
+                Some(parsed) => {
+                    span.record("db.operation.name", parsed.as_str());
+                    span.record("otel.name", parsed.as_str());
+                }
+                None => {
+                    span.record("db.operation.name", operation);
+                }
+            }
+        }
+        if !config.statements_on_error_only {
+            span.record("db.query.text", q.text());
+        }
+        if let Some(summary) = summarize_query(q.text()) {
+            span.record("db.query.summary", summary.as_str());
+        }
+        if let Some(namespace) = procedure_namespace(q.text()) {
+            span.record("neo4j.procedure.namespace", namespace.as_str());
+        }
+        if let Some(mode) = access_mode(q.text()) {
+            span.record("neo4j.query.access_mode", mode.as_str());
+        }
+        if let Some(label) = primary_label(q.text()) {
+            span.record("db.collection.name", label.as_str());
+        }
+        record_parameter_sizes(span, q.params());
+        if let Some(redactor) = &config.redactor {
+            span.record(
+                "db.query.parameters",
+                render_parameters(q.params(), redactor.as_ref()),
+            );
+        }
+    }
+
+    /// Text to record as `db.query.text` if the query fails
+    ///
+    /// `None` unless `config` holds the text back for failures, see
+    /// [`TelemetryConfig::with_statement_recording_on_error`], and would record it on `span`.
+    /// The text is copied because the query is handed to the driver before the result is
+    /// known.
+    pub(crate) fn failure_text(&self, span: &Span, config: &TelemetryConfig) -> Option<String> {
+        if !config.statements_on_error_only || !config.should_record_statement(span) {
+            return None;
+        }
+        self.text().map(str::to_owned)
+    }
+
+    /// Cypher text of traced and annotated queries; plain queries hide theirs
+    pub(crate) fn text(&self) -> Option<&str> {
+        match self {
+            Self::Plain(_) => None,
+            Self::Traced(q) => Some(q.text()),
+            Self::Annotated(q) => Some(q.query.text()),
+        }
+    }
+
+    /// Name of the operation for sampling decisions: an annotated query's explicit name, the
+    /// parsed operation of a traced query, or `fallback`
+    pub(crate) fn operation_name(&self, fallback: &'static str) -> Cow<'_, str> {
+        let q = match self {
+            Self::Plain(_) => return Cow::Borrowed(fallback),
+            Self::Traced(q) => q,
+            Self::Annotated(q) => match q.operation_name() {
+                Some(name) => return Cow::Borrowed(name),
+                None => &q.query,
+            },
+        };
+        parse_operation(q.text()).map_or(Cow::Borrowed(fallback), Cow::Owned)
+    }
+
+    pub(crate) fn into_query(self) -> Query {
+        match self {
+            Self::Plain(q) => q,
+            Self::Traced(q) => q.into(),
+            Self::Annotated(q) => q.into(),
+        }
+    }
+}
+
+impl From<Query> for Statement {
+    fn from(q: Query) -> Self {
+        Self::Plain(q)
+    }
+}
+
+impl From<TracedQuery> for Statement {
+    fn from(q: TracedQuery) -> Self {
+        Self::Traced(q)
+    }
+}
+
+/// Maximum number of collection parameters recorded per query
+///
+/// Queries with more collection parameters than this only get the first ones (by key order)
+/// recorded, which keeps the attribute count on a span bounded.
+pub const MAX_SIZED_PARAMETERS: usize = 16;
+
+/// Element count of a list parameter or key count of a map parameter
+///
+/// Returns `None` for scalar values, which have no meaningful size.
+#[must_use]
+pub fn parameter_size(value: &BoltType) -> Option<usize> {
+    match value {
+        BoltType::List(list) => Some(list.value.len()),
+        BoltType::Map(map) => Some(map.value.len()),
+        _ => None,
+    }
+}
+
+/// Sizes of the collection parameters in `params`, sorted by key and truncated to
+/// [`MAX_SIZED_PARAMETERS`] entries
+#[must_use]
+pub fn parameter_sizes(params: &BoltMap) -> Vec<(&str, usize)> {
+    let mut sizes: Vec<(&str, usize)> = params
+        .value
+        .iter()
+        .filter_map(|(key, value)| parameter_size(value).map(|size| (key.value.as_str(), size)))
+        .collect();
+    sizes.sort_unstable_by_key(|(key, _)| *key);
+    sizes.truncate(MAX_SIZED_PARAMETERS);
+    sizes
+}
+
+/// Record `db.query.parameter.<key>.size` on `span` for each collection parameter
+///
+/// Large list and map parameters (bulk `UNWIND` payloads, property maps) tend to drive query
+/// cost, so their sizes are worth surfacing even when values must stay out of telemetry.
+pub fn record_parameter_sizes(span: &Span, params: &BoltMap) {
+    for (key, size) in parameter_sizes(params) {
+        span.set_attribute(
+            format!("db.query.parameter.{key}.size"),
+            i64::try_from(size).unwrap_or(i64::MAX),
+        );
+    }
+}
+
+/// Clauses that can start a Cypher statement
+const LEADING_CLAUSES: &[&str] = &[
+    "MATCH",
+    "OPTIONAL",
+    "CREATE",
+    "MERGE",
+    "DELETE",
+    "DETACH",
+    "CALL",
+    "UNWIND",
+    "WITH",
+    "RETURN",
+    "SET",
+    "REMOVE",
+    "FOREACH",
+    "LOAD",
+    "USE",
+    "SHOW",
+    "DROP",
+    "ALTER",
+    "GRANT",
+    "REVOKE",
+    "DENY",
+    "START",
+    "STOP",
+    "TERMINATE",
+    "EXPLAIN",
+    "PROFILE",
+];
+
+/// Derive a low-cardinality operation name from Cypher text
+///
+/// Returns the leading clause of the first statement followed by the first label or
+/// relationship type it touches, e.g. `MATCH Person` or `CREATE KNOWS`. `CALL` clauses use
+/// the procedure name instead (`CALL db.labels`). Comments and leading whitespace are
+/// ignored, and only the first statement of a multi-statement script is considered.
+///
+/// Returns `None` if the text does not start with a recognised clause.
+///
+/// # Example
+///
+/// ```rust
+/// use otel_instrumentation_neo4jrs::query::parse_operation;
+///
+/// assert_eq!(
+///     parse_operation("MATCH (p:Person {name: $name}) RETURN p").as_deref(),
+///     Some("MATCH Person")
+/// );
+/// assert_eq!(parse_operation("not cypher"), None);
+/// ```
+#[must_use]
+pub fn parse_operation(cypher: &str) -> Option<String> {
+    let statement = first_statement(cypher);
+    let (keyword, rest) = split_word(&statement);
+    let mut keyword = keyword.to_ascii_uppercase();
+    if !LEADING_CLAUSES.contains(&keyword.as_str()) {
+        return None;
+    }
+
+    let mut rest = rest;
+    if keyword == "OPTIONAL" || keyword == "DETACH" || keyword == "LOAD" {
+        let (next, after) = split_word(rest);
+        if next.is_empty() {
+            return None;
+        }
+        keyword = format!("{keyword} {}", next.to_ascii_uppercase());
+        rest = after;
+    }
+
+    let target = if keyword == "CALL" {
+        procedure_name(rest)
+    } else {
+        first_label(rest)
+    };
+
+    Some(match target {
+        Some(target) => format!("{keyword} {target}"),
+        None => keyword,
+    })
+}
+
+/// Top-level namespace of the procedure a statement `CALL`s, e.g. `apoc` or `db`
+///
+/// Only the first statement is considered, and only if it starts with `CALL`. Returns `None`
+/// for `CALL { ... }` subqueries and procedures without a namespace.
+///
+/// # Example
+///
+/// ```rust
+/// use otel_instrumentation_neo4jrs::query::procedure_namespace;
+///
+/// assert_eq!(
+///     procedure_namespace("CALL db.index.fulltext.queryNodes('idx', $q)").as_deref(),
+///     Some("db")
+/// );
+/// assert_eq!(procedure_namespace("MATCH (n) RETURN n"), None);
+/// ```
+#[must_use]
+pub fn procedure_namespace(cypher: &str) -> Option<String> {
+    let statement = first_statement(cypher);
+    let (keyword, rest) = split_word(&statement);
+    if !keyword.eq_ignore_ascii_case("CALL") {
+        return None;
+    }
+    let (namespace, _) = procedure_name(rest)?.split_once('.')?;
+    Some(namespace.to_owned())
+}
+
+/// First node label of the first statement, recorded as `db.collection.name`
+///
+/// Relationship types and labels inside map literals are skipped, and `CALL`ed procedures
+/// have no primary label. Returns `None` if the statement does not start with a recognised
+/// clause or matches no labelled node.
+///
+/// # Example
+///
+/// ```rust
+/// use otel_instrumentation_neo4jrs::query::primary_label;
+///
+/// assert_eq!(
+///     primary_label("MATCH ()-[:PLACED]->(o:Order) RETURN o").as_deref(),
+///     Some("Order")
+/// );
+/// assert_eq!(primary_label("MATCH (n) RETURN n"), None);
+/// ```
+#[must_use]
+pub fn primary_label(cypher: &str) -> Option<String> {
+    let statement = first_statement(cypher);
+    let (keyword, rest) = split_word(&statement);
+    let keyword = keyword.to_ascii_uppercase();
+    if !LEADING_CLAUSES.contains(&keyword.as_str()) || keyword == "CALL" {
+        return None;
+    }
+
+    let mut braces = 0_usize;
+    let mut brackets = 0_usize;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '{' => braces += 1,
+            '}' => braces = braces.saturating_sub(1),
+            '[' => brackets += 1,
+            ']' => brackets = brackets.saturating_sub(1),
+            ':' if braces == 0 && brackets == 0 => {
+                if let Some(label) = label_at(&rest[i + 1..]) {
+                    return Some(label.to_owned());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Whether a statement reads data, writes data or changes the schema
+///
+/// Recorded as `neo4j.query.access_mode` so read/write skew in cluster routing shows up in
+/// traces and metrics, see [`access_mode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AccessMode {
+    /// Only reads data, e.g. `MATCH ... RETURN`
+    Read,
+    /// Creates, updates or deletes data, e.g. `CREATE`, `MERGE`, `SET` or `DELETE`
+    Write,
+    /// Creates or drops an index or constraint
+    Schema,
+}
+
+impl AccessMode {
+    /// The attribute value: `read`, `write` or `schema`
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Schema => "schema",
+        }
+    }
+}
+
+/// Clauses that make a statement a [`AccessMode::Write`] wherever they appear in it
+const WRITE_CLAUSES: &[&str] = &["CREATE", "MERGE", "DELETE", "SET", "REMOVE", "FOREACH"];
+
+/// Classify the first statement of `cypher` as a read, a write or a schema change
+///
+/// `CREATE` and `DROP` of an index or constraint are [`AccessMode::Schema`]. Otherwise a
+/// statement containing any `CREATE`, `MERGE`, `DELETE`, `SET`, `REMOVE` or `FOREACH` clause,
+/// including in a subquery, is a [`AccessMode::Write`], and any other query is a
+/// [`AccessMode::Read`]. `EXPLAIN` and `PROFILE` classify the statement they prefix.
+///
+/// Returns `None` for text that does not start with a recognised clause, for procedure
+/// calls, whose effect depends on the procedure, and for administration commands.
+///
+/// # Example
+///
+/// ```rust
+/// use otel_instrumentation_neo4jrs::query::{access_mode, AccessMode};
+///
+/// assert_eq!(access_mode("MATCH (p:Person) RETURN p"), Some(AccessMode::Read));
+/// assert_eq!(access_mode("MATCH (p:Person) SET p.seen = true"), Some(AccessMode::Write));
+/// assert_eq!(
+///     access_mode("CREATE INDEX person_name FOR (p:Person) ON (p.name)"),
+///     Some(AccessMode::Schema)
+/// );
+/// ```
+#[must_use]
+pub fn access_mode(cypher: &str) -> Option<AccessMode> {
+    let statement = first_statement(cypher);
+    let (mut keyword, mut rest) = split_word(&statement);
+    if keyword.eq_ignore_ascii_case("EXPLAIN") || keyword.eq_ignore_ascii_case("PROFILE") {
+        (keyword, rest) = split_word(rest);
+    }
+
+    match keyword.to_ascii_uppercase().as_str() {
+        "CREATE" | "DROP" if creates_schema_object(rest) => Some(AccessMode::Schema),
+        "CALL" if procedure_name(rest).is_some() => None,
+        "SHOW" => Some(AccessMode::Read),
+        "DROP" | "ALTER" | "GRANT" | "REVOKE" | "DENY" | "START" | "STOP" | "TERMINATE" => None,
+        keyword if LEADING_CLAUSES.contains(&keyword) => {
+            if clause_words(&statement).any(|word| WRITE_CLAUSES.contains(&word.as_str())) {
+                Some(AccessMode::Write)
+            } else {
+                Some(AccessMode::Read)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Whether the words after `CREATE` or `DROP` name an index or constraint, e.g.
+/// `RANGE INDEX` or `CONSTRAINT`
+fn creates_schema_object(mut rest: &str) -> bool {
+    // Index kinds and `OR REPLACE` come before the object
+    for _ in 0..3 {
+        let (word, after) = split_word(rest);
+        if word.eq_ignore_ascii_case("INDEX") || word.eq_ignore_ascii_case("CONSTRAINT") {
+            return true;
+        }
+        if word.is_empty() {
+            return false;
+        }
+        rest = after;
+    }
+    false
+}
+
+/// Upper-cased words of `statement` that can be clause keywords, skipping labels, property
+/// keys, parameters and quoted names
+fn clause_words(statement: &str) -> impl Iterator<Item = String> + '_ {
+    let mut prev = ' ';
+    let mut chars = statement.char_indices();
+    std::iter::from_fn(move || {
+        while let Some((i, c)) = chars.next() {
+            if c == '`' {
+                for quoted in chars.by_ref().map(|(_, c)| c) {
+                    if quoted == '`' {
+                        break;
+                    }
+                }
+            } else if c.is_ascii_alphabetic()
+                && !(prev.is_alphanumeric() || matches!(prev, '_' | '.' | '$' | ':'))
+            {
+                let (word, _) = split_word(&statement[i..]);
+                for _ in 1..word.len() {
+                    chars.next();
+                }
+                prev = word.chars().last().unwrap_or(c);
+                return Some(word.to_ascii_uppercase());
+            }
+            prev = c;
+        }
+        None
+    })
+}
+
+/// Keywords kept in a [`summarize_query`] summary besides the [`LEADING_CLAUSES`]
+const SUMMARY_KEYWORDS: &[&str] = &["WHERE", "ORDER", "SKIP", "LIMIT", "UNION", "ON", "YIELD"];
+
+/// Maximum length of a [`summarize_query`] summary, in bytes
+pub const MAX_SUMMARY_LEN: usize = 255;
+
+/// Normalise Cypher text into a low-cardinality summary for `db.query.summary`
+///
+/// Keeps the clause keywords, labels, relationship types and procedure names of the first
+/// statement in order and drops everything else: literals, parameters, variables, property
+/// keys and comments. Queries that differ only in their values therefore share a summary,
+/// e.g. `MATCH Person WHERE RETURN`. Summaries are cut at a word boundary so they never
+/// exceed [`MAX_SUMMARY_LEN`].
+///
+/// Returns `None` if the text does not start with a recognised clause.
+///
+/// # Example
+///
+/// ```rust
+/// use otel_instrumentation_neo4jrs::query::summarize_query;
+///
+/// assert_eq!(
+///     summarize_query("MATCH (p:Person) WHERE p.age > 42 AND p.name = $name RETURN p")
+///         .as_deref(),
+///     Some("MATCH Person WHERE RETURN")
+/// );
+/// ```
+#[must_use]
+pub fn summarize_query(cypher: &str) -> Option<String> {
+    let statement = first_statement(cypher);
+    let (keyword, _) = split_word(&statement);
+    if !LEADING_CLAUSES.contains(&keyword.to_ascii_uppercase().as_str()) {
+        return None;
+    }
+
+    let mut summary = String::new();
+    let mut depth = 0_usize;
+    let mut prev = ' ';
+    let mut chars = statement.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            '`' => {
+                // Quoted names are only kept when they follow a `:`, handled below
+                for quoted in chars.by_ref().map(|(_, c)| c) {
+                    if quoted == '`' {
+                        break;
+                    }
+                }
+            }
+            ':' if depth == 0 => {
+                if let Some(label) = label_at(&statement[i + 1..]) {
+                    push_summary_part(&mut summary, label);
+                }
+            }
+            c if c.is_ascii_alphabetic()
+                && !(prev.is_alphanumeric() || matches!(prev, '_' | '.' | '$' | ':')) =>
+            {
+                let rest = &statement[i..];
+                let (word, after) = split_word(rest);
+                let upper = word.to_ascii_uppercase();
+                if LEADING_CLAUSES.contains(&upper.as_str())
+                    || SUMMARY_KEYWORDS.contains(&upper.as_str())
+                {
+                    push_summary_part(&mut summary, &upper);
+                    if upper == "CALL" {
+                        if let Some(name) = procedure_name(after) {
+                            push_summary_part(&mut summary, name);
+                        }
+                    }
+                }
+                // Skip the rest of the word so its letters aren't scanned again
+                let mut last = c;
+                for _ in 1..word.len() {
+                    if let Some((_, c)) = chars.next() {
+                        last = c;
+                    }
+                }
+                prev = last;
+                continue;
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+
+    Some(summary)
+}
+
+/// Append `part` to `summary` unless that would exceed [`MAX_SUMMARY_LEN`]
+fn push_summary_part(summary: &mut String, part: &str) {
+    let separator = usize::from(!summary.is_empty());
+    if summary.len() + separator + part.len() > MAX_SUMMARY_LEN {
+        return;
+    }
+    if separator == 1 {
+        summary.push(' ');
+    }
+    summary.push_str(part);
+}
+
+/// The first non-empty statement of `cypher`, with comments removed and string literals
+/// blanked out so their contents are never mistaken for syntax
+fn first_statement(cypher: &str) -> String {
+    let mut out = String::with_capacity(cypher.len());
+    let mut chars = cypher.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                out.push(' ');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+                out.push(' ');
+            }
+            '\'' | '"' => {
+                let mut escaped = false;
+                for inner in chars.by_ref() {
+                    if escaped {
+                        escaped = false;
+                    } else if inner == '\\' {
+                        escaped = true;
+                    } else if inner == c {
+                        break;
+                    }
+                }
+                out.push_str("''");
+            }
+            ';' if out.trim().is_empty() => out.clear(),
+            ';' => break,
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// Split the leading alphabetic word off `text`, skipping leading whitespace
+fn split_word(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    let end = text
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(text.len());
+    text.split_at(end)
+}
+
+/// Dotted procedure name following a `CALL`, or `None` for `CALL { ... }` subqueries
+fn procedure_name(text: &str) -> Option<&str> {
+    let text = text.trim_start();
+    let end = text
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(text.len());
+    let name = &text[..end];
+    (!name.is_empty()).then_some(name)
+}
+
+/// First `:Label` or `:TYPE` in `text` that is not inside a map literal
+fn first_label(text: &str) -> Option<&str> {
+    let mut depth = 0_usize;
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ':' if depth == 0 => {
+                if let Some(label) = label_at(&text[i + 1..]) {
+                    return Some(label);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// The label or relationship type at the start of `after`, the text following a `:`
+fn label_at(after: &str) -> Option<&str> {
+    if let Some(quoted) = after.strip_prefix('`') {
+        return quoted.split('`').next().filter(|l| !l.is_empty());
+    }
+    let end = after
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(after.len());
+    let label = &after[..end];
+    label
+        .starts_with(|c: char| c.is_alphabetic() || c == '_')
+        .then_some(label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redact::{MaskAllRedactor, RedactAction, Redactor};
+    use crate::test_support::{attribute, CapturedSpans};
+    use opentelemetry::propagation::text_map_propagator::FieldIter;
+    use opentelemetry::propagation::{Extractor, Injector};
+    use opentelemetry::trace::TraceContextExt;
+    use opentelemetry::Value;
+    use opentelemetry_sdk::trace::Sampler;
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn params(entries: Vec<(&str, BoltType)>) -> BoltMap {
+        let mut map = BoltMap::new();
+        for (key, value) in entries {
+            map.put(key.into(), value);
+        }
+        map
+    }
+
+    fn recorded_sizes(params: &BoltMap) -> opentelemetry_sdk::trace::SpanData {
+        let capture = CapturedSpans::install();
+        let span = tracing::info_span!("query");
+        record_parameter_sizes(&span, params);
+        drop(span);
+        capture.span("query")
+    }
+
+    #[test]
+    fn test_traced_query_forwards_parameters() {
+        let traced = TracedQuery::new("CREATE (n:Person {name: $name, age: $age})")
+            .param("name", "Alice")
+            .param("age", 30);
+
+        assert_eq!(traced.params().len(), 2);
+
+        let query: Query = traced.into();
+        assert!(query.has_param_key("name"));
+        assert!(query.has_param_key("age"));
+        assert!(!query.has_param_key("email"));
+    }
+
+    #[test]
+    fn test_statement_records_text_only_when_traced() {
+        let capture = CapturedSpans::install();
+
+        let traced: Statement = TracedQuery::new("RETURN $ids")
+            .param("ids", vec![1_i64, 2])
+            .into();
+        let span = tracing::info_span!(
+            "traced",
+            otel.name = "run",
+            db.operation.name = tracing::field::Empty,
+            db.query.text = tracing::field::Empty,
+            db.query.summary = tracing::field::Empty,
+            db.query.parameters = tracing::field::Empty
+        );
+        traced.record(&span, "run", &TelemetryConfig::default());
+        drop(span);
+
+        let plain: Statement = Query::new("RETURN 1".to_string()).into();
+        let span = tracing::info_span!(
+            "plain",
+            db.operation.name = tracing::field::Empty,
+            db.query.text = tracing::field::Empty
+        );
+        plain.record(&span, "run", &TelemetryConfig::default());
+        drop(span);
+
+        let traced = capture.span("RETURN");
+        assert_eq!(
+            attribute(&traced, "db.operation.name"),
+            Some(&Value::from("RETURN"))
+        );
+        assert_eq!(
+            attribute(&traced, "db.query.text"),
+            Some(&Value::from("RETURN $ids"))
+        );
+        assert_eq!(
+            attribute(&traced, "db.query.summary"),
+            Some(&Value::from("RETURN"))
+        );
+        assert_eq!(
+            attribute(&traced, "db.query.parameter.ids.size"),
+            Some(&Value::I64(2))
+        );
+        assert!(attribute(&traced, "db.query.parameters").is_none());
+        let plain = capture.span("plain");
+        assert_eq!(
+            attribute(&plain, "db.operation.name"),
+            Some(&Value::from("run"))
+        );
+        assert!(attribute(&plain, "db.query.text").is_none());
+    }
+
+    #[test]
+    fn test_statement_records_parameter_count_without_values() {
+        let capture = CapturedSpans::install();
+        let statements: Vec<Statement> = vec![
+            TracedQuery::new("RETURN 1").into(),
+            TracedQuery::new("RETURN $name")
+                .param("name", "Alice")
+                .into(),
+            Statement::Annotated(
+                TracedQuery::new("RETURN $name, $email, $age")
+                    .param("name", "Alice")
+                    .param("email", "alice@example.com")
+                    .param("age", 42_i64)
+                    .with_trace_comment("request=42"),
+            ),
+        ];
+        for statement in statements {
+            let span = tracing::info_span!(
+                "traced",
+                otel.name = "run",
+                db.operation.name = tracing::field::Empty,
+                db.query.text = tracing::field::Empty,
+                db.query.parameter_count = tracing::field::Empty,
+                db.query.parameters = tracing::field::Empty
+            );
+            statement.record(&span, "run", &TelemetryConfig::default());
+        }
+
+        let spans = capture.spans();
+        let counts: Vec<Option<&Value>> = spans
+            .iter()
+            .map(|span| attribute(span, "db.query.parameter_count"))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                Some(&Value::I64(0)),
+                Some(&Value::I64(1)),
+                Some(&Value::I64(3))
+            ]
+        );
+
+        let leaked = spans.iter().any(|span| {
+            span.attributes.iter().any(|kv| {
+                let value = kv.value.as_str();
+                value.contains("Alice") || value.contains("alice@example.com")
+            })
+        });
+        assert!(!leaked, "parameter values must not be recorded");
+    }
+
+    /// Writes a fixed-format `traceparent` from the context's span, like the W3C propagator
+    #[derive(Debug)]
+    struct MockPropagator(Vec<String>);
+
+    impl TextMapPropagator for MockPropagator {
+        fn inject_context(&self, cx: &Context, injector: &mut dyn Injector) {
+            let span = cx.span();
+            let span_context = span.span_context();
+            if span_context.is_valid() {
+                injector.set(
+                    "traceparent",
+                    format!(
+                        "00-{}-{}-01",
+                        span_context.trace_id(),
+                        span_context.span_id()
+                    ),
+                );
+            }
+        }
+
+        fn extract_with_context(&self, cx: &Context, _: &dyn Extractor) -> Context {
+            cx.clone()
+        }
+
+        fn fields(&self) -> FieldIter<'_> {
+            FieldIter::new(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_trace_context_binds_traceparent() {
+        let capture = CapturedSpans::install();
+        let propagator = MockPropagator(vec!["traceparent".to_owned()]);
+
+        let (traced, expected) = tracing::info_span!("request").in_scope(|| {
+            let cx = Span::current().context();
+            let span_context = cx.span().span_context().clone();
+            let q = TracedQuery::new("CREATE (:Order {traceparent: $__traceparent})")
+                .with_traceparent(traceparent(&propagator, &cx));
+            let expected = format!(
+                "00-{}-{}-01",
+                span_context.trace_id(),
+                span_context.span_id()
+            );
+            (q, expected)
+        });
+        drop(capture);
+
+        assert_eq!(
+            traced.params().get::<String>(TRACEPARENT_PARAM).unwrap(),
+            expected
+        );
+
+        // Without a span there is nothing to propagate, but the Cypher must still bind
+        let detached = TracedQuery::new("RETURN $__traceparent")
+            .with_traceparent(traceparent(&propagator, &Context::new()));
+        assert!(matches!(
+            detached.params().value.get(TRACEPARENT_PARAM),
+            Some(BoltType::Null(_))
+        ));
+    }
+
+    #[test]
+    fn test_annotated_query_prepends_comment() {
+        let annotated = TracedQuery::new("MATCH (p:Person {name: $name}) RETURN p")
+            .param("name", "Alice")
+            .with_trace_comment("request=42\nDETACH DELETE everything");
+
+        // A line break must not let the comment escape into the statement
+        assert_eq!(
+            annotated.cypher(),
+            "// request=42 DETACH DELETE everything\nMATCH (p:Person {name: $name}) RETURN p"
+        );
+        assert_eq!(annotated.operation_name(), None);
+
+        let query: Query = annotated.into();
+        assert!(query.has_param_key("name"));
+    }
+
+    #[test]
+    fn test_annotated_operation_name_overrides_parsed_name() {
+        let capture = CapturedSpans::install();
+
+        let RunnableQuery(annotated) = TracedQuery::new("MATCH (p:Person) RETURN p")
+            .with_trace_comment("job=report")
+            .with_operation_name("list_people")
+            .into();
+        let span = tracing::info_span!(
+            "annotated",
+            otel.name = "run",
+            db.operation.name = tracing::field::Empty,
+            db.query.text = tracing::field::Empty,
+            db.query.parameters = tracing::field::Empty
+        );
+        annotated.record(&span, "run", &TelemetryConfig::default());
+        drop(span);
+
+        let span = capture.span("list_people");
+        assert_eq!(
+            attribute(&span, "db.operation.name"),
+            Some(&Value::from("list_people"))
+        );
+        assert_eq!(
+            attribute(&span, "db.query.text"),
+            Some(&Value::from("MATCH (p:Person) RETURN p"))
+        );
+    }
+
+    #[test]
+    fn test_operation_allowlist_collapses_unlisted_operations() {
+        let capture = CapturedSpans::install();
+        let config = TelemetryConfig::new()
+            .with_operation_allowlist(HashSet::from(["MATCH Person".to_string()]));
+
+        for cypher in [
+            "MATCH (p:Person) RETURN p",
+            "MATCH (g:Generated_42) RETURN g",
+        ] {
+            let traced: Statement = TracedQuery::new(cypher).into();
+            let span = tracing::info_span!(
+                "run",
+                otel.name = "run",
+                db.operation.name = tracing::field::Empty,
+                db.query.text = tracing::field::Empty
+            );
+            traced.record(&span, "run", &config);
+        }
+
+        let names: Vec<_> = capture
+            .spans()
+            .iter()
+            .map(|s| {
+                (
+                    s.name.to_string(),
+                    attribute(s, "db.operation.name").cloned(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (
+                    "MATCH Person".to_string(),
+                    Some(Value::from("MATCH Person"))
+                ),
+                ("run".to_string(), Some(Value::from("run"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_statement_recording_disabled() {
+        let capture = CapturedSpans::install();
+        let config = TelemetryConfig::new().with_statement_recording(false);
+
+        let traced: Statement = TracedQuery::new("MATCH (p:Person) RETURN p").into();
+        let span = tracing::info_span!(
+            "run",
+            otel.name = "run",
+            db.operation.name = tracing::field::Empty,
+            db.query.text = tracing::field::Empty
+        );
+        traced.record(&span, "run", &config);
+        drop(span);
+
+        let span = capture.span("run");
+        assert_eq!(
+            attribute(&span, "db.operation.name"),
+            Some(&Value::from("run"))
+        );
+        assert!(attribute(&span, "db.query.text").is_none());
+    }
+
+    #[test]
+    fn test_statement_text_held_for_failures() {
+        let capture = CapturedSpans::install();
+        let config = TelemetryConfig::new().with_statement_recording_on_error(true);
+
+        let traced: Statement = TracedQuery::new("MATCH (p:Person) RETURN p").into();
+        let span = tracing::info_span!(
+            "run",
+            otel.name = "run",
+            db.operation.name = tracing::field::Empty,
+            db.query.text = tracing::field::Empty,
+            db.query.summary = tracing::field::Empty
+        );
+        traced.record(&span, "run", &config);
+        assert_eq!(
+            traced.failure_text(&span, &config).as_deref(),
+            Some("MATCH (p:Person) RETURN p")
+        );
+        assert_eq!(
+            traced.failure_text(&span, &TelemetryConfig::default()),
+            None
+        );
+        let plain: Statement = Query::new("RETURN 1".to_string()).into();
+        assert_eq!(plain.failure_text(&span, &config), None);
+        drop(span);
+
+        // Everything but the text is recorded as usual
+        let span = capture.span("MATCH Person");
+        assert!(attribute(&span, "db.query.text").is_none());
+        assert!(attribute(&span, "db.query.summary").is_some());
+    }
+
+    #[test]
+    fn test_statement_records_redacted_parameters() {
+        let capture = CapturedSpans::install();
+        let config = TelemetryConfig::new().with_redactor(Box::new(MaskAllRedactor));
+
+        let traced: Statement = TracedQuery::new("MATCH (u:User {email: $email}) RETURN u")
+            .param("email", "alice@example.com")
+            .into();
+        let span = tracing::info_span!(
+            "traced",
+            otel.name = "run",
+            db.operation.name = tracing::field::Empty,
+            db.query.text = tracing::field::Empty,
+            db.query.parameters = tracing::field::Empty
+        );
+        traced.record(&span, "run", &config);
+        drop(span);
+
+        assert_eq!(
+            attribute(&capture.span("MATCH User"), "db.query.parameters"),
+            Some(&Value::from(r#"{email: "***"}"#))
+        );
+    }
+
+    /// Counts how often parameter values are inspected
+    struct CountingRedactor(Arc<AtomicUsize>);
+
+    impl Redactor for CountingRedactor {
+        fn redact(&self, _key: &str, _value: &BoltType) -> RedactAction {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            RedactAction::Keep
+        }
+    }
+
+    fn record_with_sampler(sampler: Sampler, config: TelemetryConfig) -> (usize, Option<Value>) {
+        let capture = CapturedSpans::install_with_sampler(sampler);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let config = config.with_redactor(Box::new(CountingRedactor(calls.clone())));
+
+        let traced: Statement = TracedQuery::new("MATCH (u:User {id: $id}) RETURN u")
+            .param("id", 1)
+            .into();
+        let span = tracing::info_span!(
+            "traced",
+            db.operation.name = tracing::field::Empty,
+            db.query.text = tracing::field::Empty,
+            db.query.parameters = tracing::field::Empty
+        );
+        traced.record(&span, "run", &config);
+        drop(span);
+
+        let text = capture
+            .spans()
+            .first()
+            .and_then(|s| attribute(s, "db.query.text").cloned());
+        (calls.load(Ordering::SeqCst), text)
+    }
+
+    #[test]
+    fn test_statement_skipped_when_unsampled() {
+        let (calls, text) = record_with_sampler(Sampler::AlwaysOff, TelemetryConfig::new());
+        assert_eq!(calls, 0);
+        assert!(text.is_none());
+
+        let (calls, text) = record_with_sampler(Sampler::AlwaysOn, TelemetryConfig::new());
+        assert_eq!(calls, 1);
+        assert!(text.is_some());
+    }
+
+    #[test]
+    fn test_statement_recorded_when_unsampled_if_configured() {
+        let config = TelemetryConfig::new().with_sampled_statements_only(false);
+        let (calls, _) = record_with_sampler(Sampler::AlwaysOff, config);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_parse_operation_keyword_and_label() {
+        let cases = [
+            ("MATCH (p:Person) RETURN p", Some("MATCH Person")),
+            ("CREATE (n:Person {name: $name})", Some("CREATE Person")),
+            ("MERGE (a)-[:KNOWS]->(b)", Some("MERGE KNOWS")),
+            ("MATCH (n {name: 'x'}) DELETE n", Some("MATCH")),
+            (
+                "optional match (n:`Odd Label`) return n",
+                Some("OPTIONAL MATCH Odd Label"),
+            ),
+            ("DETACH DELETE n", Some("DETACH DELETE")),
+            ("CALL db.labels()", Some("CALL db.labels")),
+            ("CALL { MATCH (n:Person) RETURN n } RETURN n", Some("CALL")),
+            (
+                "UNWIND $rows AS row CREATE (:Item {id: row.id})",
+                Some("UNWIND Item"),
+            ),
+            ("RETURN 1", Some("RETURN")),
+            ("", None),
+            ("hello world", None),
+        ];
+
+        for (cypher, expected) in cases {
+            assert_eq!(parse_operation(cypher).as_deref(), expected, "{cypher}");
+        }
+    }
+
+    #[test]
+    fn test_procedure_namespace() {
+        let cases = [
+            ("CALL db.labels()", Some("db")),
+            ("call apoc.periodic.iterate($a, $b, {})", Some("apoc")),
+            (
+                "// admin\nCALL dbms.components() YIELD versions",
+                Some("dbms"),
+            ),
+            ("CALL myProcedure()", None),
+            ("CALL { MATCH (n) RETURN n } RETURN n", None),
+            ("MATCH (n) CALL db.labels() YIELD label RETURN label", None),
+        ];
+
+        for (cypher, expected) in cases {
+            assert_eq!(procedure_namespace(cypher).as_deref(), expected, "{cypher}");
+        }
+    }
+
+    #[test]
+    fn test_access_mode() {
+        let cases = [
+            ("MATCH (p:Person) RETURN p", Some(AccessMode::Read)),
+            ("RETURN 1", Some(AccessMode::Read)),
+            (
+                "OPTIONAL MATCH (p)-[:KNOWS]->(f) RETURN f",
+                Some(AccessMode::Read),
+            ),
+            ("CREATE (p:Person {name: $name})", Some(AccessMode::Write)),
+            (
+                "merge (p:Person {id: $id}) ON CREATE SET p.new = true",
+                Some(AccessMode::Write),
+            ),
+            ("MATCH (p:Person) DETACH DELETE p", Some(AccessMode::Write)),
+            (
+                "MATCH (p) WHERE p.set = 'CREATE' RETURN p",
+                Some(AccessMode::Read),
+            ),
+            (
+                "MATCH (p:`SET`) RETURN p.delete, $create",
+                Some(AccessMode::Read),
+            ),
+            (
+                "UNWIND $rows AS row CALL { WITH row CREATE (:Row) }",
+                Some(AccessMode::Write),
+            ),
+            ("PROFILE MATCH (p) SET p.x = 1", Some(AccessMode::Write)),
+            (
+                "CREATE INDEX person_name FOR (p:Person) ON (p.name)",
+                Some(AccessMode::Schema),
+            ),
+            (
+                "CREATE TEXT INDEX IF NOT EXISTS FOR (p:Person) ON (p.bio)",
+                Some(AccessMode::Schema),
+            ),
+            (
+                "CREATE CONSTRAINT FOR (p:Person) REQUIRE p.id IS UNIQUE",
+                Some(AccessMode::Schema),
+            ),
+            ("DROP INDEX person_name", Some(AccessMode::Schema)),
+            ("SHOW INDEXES", Some(AccessMode::Read)),
+            ("CALL db.labels()", None),
+            ("DROP DATABASE archive", None),
+            ("not cypher", None),
+        ];
+
+        for (cypher, expected) in cases {
+            assert_eq!(access_mode(cypher), expected, "{cypher}");
+        }
+    }
+
+    #[test]
+    fn test_primary_label() {
+        let cases = [
+            ("MATCH (p:Person {name: $name}) RETURN p", Some("Person")),
+            ("MATCH (a)-[:KNOWS]->(b:Person) RETURN b", Some("Person")),
+            ("MATCH (a)-[r:KNOWS {since: 2020}]->(b) RETURN b", None),
+            ("CREATE (o:`Order Line`:Archived)", Some("Order Line")),
+            ("MERGE (n {kind: 'a:B'}) RETURN n", None),
+            ("CREATE INDEX FOR (p:Person) ON (p.name)", Some("Person")),
+            ("CALL db.labels()", None),
+            ("MATCH (n) RETURN n", None),
+            ("not cypher", None),
+        ];
+
+        for (cypher, expected) in cases {
+            assert_eq!(primary_label(cypher).as_deref(), expected, "{cypher}");
+        }
+    }
+
+    #[test]
+    fn test_statement_records_collection_name() {
+        let capture = CapturedSpans::install();
+
+        let statement: Statement = TracedQuery::new("MATCH (o:Order) RETURN o").into();
+        let span = tracing::info_span!(
+            "orders",
+            db.operation.name = tracing::field::Empty,
+            db.collection.name = tracing::field::Empty
+        );
+        statement.record(&span, "run", &TelemetryConfig::default());
+        drop(span);
+
+        assert_eq!(
+            attribute(&capture.span("orders"), "db.collection.name"),
+            Some(&Value::from("Order"))
+        );
+
+        let statement: Statement = TracedQuery::new("MATCH (n) RETURN n").into();
+        let span = tracing::info_span!("unlabelled", db.collection.name = tracing::field::Empty);
+        statement.record(&span, "run", &TelemetryConfig::default());
+        drop(span);
+        assert!(attribute(&capture.span("unlabelled"), "db.collection.name").is_none());
+    }
+
+    #[test]
+    fn test_statement_records_access_mode() {
+        let capture = CapturedSpans::install();
+
+        let statement: Statement = TracedQuery::new("MATCH (p:Person) SET p.seen = true").into();
+        let span = tracing::info_span!(
+            "write",
+            db.operation.name = tracing::field::Empty,
+            neo4j.query.access_mode = tracing::field::Empty
+        );
+        statement.record(&span, "run", &TelemetryConfig::default());
+        drop(span);
+
+        assert_eq!(
+            attribute(&capture.span("write"), "neo4j.query.access_mode"),
+            Some(&Value::from("write"))
+        );
+    }
+
+    #[test]
+    fn test_statement_records_procedure_namespace() {
+        let capture = CapturedSpans::install();
+
+        let statement: Statement = TracedQuery::new("CALL apoc.meta.stats()").into();
+        let span = tracing::info_span!(
+            "call",
+            db.operation.name = tracing::field::Empty,
+            neo4j.procedure.namespace = tracing::field::Empty
+        );
+        statement.record(&span, "run", &TelemetryConfig::default());
+        drop(span);
+
+        let span = capture.span("call");
+        assert_eq!(
+            attribute(&span, "db.operation.name"),
+            Some(&Value::from("CALL apoc.meta.stats"))
+        );
+        assert_eq!(
+            attribute(&span, "neo4j.procedure.namespace"),
+            Some(&Value::from("apoc"))
+        );
+    }
+
+    #[test]
+    fn test_parse_operation_skips_comments_and_whitespace() {
+        assert_eq!(
+            parse_operation("\n\t  // find people\n  MATCH (p:Person) RETURN p").as_deref(),
+            Some("MATCH Person")
+        );
+        assert_eq!(
+            parse_operation("/* multi\nline */ CREATE (:Movie)").as_deref(),
+            Some("CREATE Movie")
+        );
+        assert_eq!(
+            parse_operation("MATCH (n {note: 'a:b // c'}) SET n:Seen").as_deref(),
+            Some("MATCH Seen")
+        );
+    }
+
+    #[test]
+    fn test_parse_operation_uses_first_statement() {
+        assert_eq!(
+            parse_operation("CREATE (:A); MATCH (b:B) RETURN b").as_deref(),
+            Some("CREATE A")
+        );
+        assert_eq!(
+            parse_operation(" ; MATCH (b:B) RETURN b").as_deref(),
+            Some("MATCH B")
+        );
+        assert_eq!(
+            parse_operation("MATCH (n) WHERE n.s = ';' RETURN n:Label").as_deref(),
+            Some("MATCH Label")
+        );
+    }
+
+    #[test]
+    fn test_summarize_query_strips_literals_and_parameters() {
+        let cases = [
+            (
+                "MATCH (p:Person) WHERE p.name = 'Alice' AND p.age > 42 RETURN p",
+                Some("MATCH Person WHERE RETURN"),
+            ),
+            (
+                "MATCH (p:Person) WHERE p.name = $name AND p.age > $age RETURN p",
+                Some("MATCH Person WHERE RETURN"),
+            ),
+            (
+                "MERGE (a:Person {id: $id})-[:KNOWS]->(b:Person {id: 7}) ON CREATE SET a.seen = 1",
+                Some("MERGE Person KNOWS Person ON CREATE SET"),
+            ),
+            (
+                "UNWIND $rows AS row CREATE (:`Line Item` {id: row.id, note: \"match\"})",
+                Some("UNWIND CREATE Line Item"),
+            ),
+            (
+                "CALL db.index.fulltext.queryNodes('idx', $q) YIELD node RETURN node",
+                Some("CALL db.index.fulltext.queryNodes YIELD RETURN"),
+            ),
+            (
+                "match (n) where n.create = true return n order by n.limit limit 10",
+                Some("MATCH WHERE RETURN ORDER LIMIT"),
+            ),
+            ("RETURN $x", Some("RETURN")),
+            ("hello world", None),
+        ];
+
+        for (cypher, expected) in cases {
+            assert_eq!(summarize_query(cypher).as_deref(), expected, "{cypher}");
+        }
+    }
+
+    #[test]
+    fn test_summarize_query_ignores_comments_and_later_statements() {
+        assert_eq!(
+            summarize_query("// CREATE (:Nope)\nMATCH (n:A) /* :B */ RETURN n; DELETE x")
+                .as_deref(),
+            Some("MATCH A RETURN")
+        );
+    }
+
+    #[test]
+    fn test_summarize_query_bounded() {
+        let cypher = format!("MATCH {}", "(:Label) ".repeat(100));
+        let summary = summarize_query(&cypher).unwrap();
+
+        assert!(summary.len() <= MAX_SUMMARY_LEN);
+        assert!(summary.ends_with("Label"));
+    }
+
+    #[test]
+    fn test_list_parameter_size() {
+        let params = params(vec![
+            ("ids", vec![1_i64, 2, 3].into()),
+            ("name", "Alice".into()),
+        ]);
+        let span = recorded_sizes(&params);
+
+        assert_eq!(
+            attribute(&span, "db.query.parameter.ids.size"),
+            Some(&Value::I64(3))
+        );
+        assert!(attribute(&span, "db.query.parameter.name.size").is_none());
+    }
+
+    #[test]
+    fn test_map_parameter_size() {
+        let props: HashMap<String, BoltType> = HashMap::from([
+            ("name".to_string(), "Alice".into()),
+            ("age".to_string(), 30_i64.into()),
+        ]);
+        let params = params(vec![("props", props.into())]);
+        let span = recorded_sizes(&params);
+
+        assert_eq!(
+            attribute(&span, "db.query.parameter.props.size"),
+            Some(&Value::I64(2))
+        );
+    }
+
+    #[test]
+    fn test_parameter_sizes_truncated() {
+        let entries = (0..MAX_SIZED_PARAMETERS + 4)
+            .map(|i| (format!("list{i:02}"), BoltType::from(vec![1_i64])))
+            .collect::<Vec<_>>();
+        let mut map = BoltMap::new();
+        for (key, value) in entries {
+            map.put(key.into(), value);
+        }
+
+        let sizes = parameter_sizes(&map);
+        assert_eq!(sizes.len(), MAX_SIZED_PARAMETERS);
+        assert_eq!(sizes[0], ("list00", 1));
+    }
+}
@@ -0,0 +1,172 @@
+//! Parameter redaction for statement recording
+//!
+//! Query parameter values are only recorded when a [`Redactor`] is installed, and every
+//! value passes through it first. The redactor decides per parameter whether the value is
+//! kept, masked or left out of `db.query.parameters` entirely.
+
+use neo4rs::{BoltMap, BoltType};
+use std::fmt::Write as _;
+
+/// Placeholder written in place of masked parameter values
+pub const MASK: &str = "***";
+
+/// What to do with a parameter value before it is recorded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RedactAction {
+    /// Record the value as-is
+    Keep,
+    /// Record the parameter with its value replaced by [`MASK`]
+    Mask,
+    /// Leave the parameter out entirely
+    Drop,
+}
+
+/// Decides how each query parameter is recorded on spans
+///
+/// # Example
+///
+/// ```rust
+/// use neo4rs::BoltType;
+/// use otel_instrumentation_neo4jrs::redact::{RedactAction, Redactor};
+///
+/// /// Masks anything that looks like personal data, keeps the rest
+/// struct PiiRedactor;
+///
+/// impl Redactor for PiiRedactor {
+///     fn redact(&self, key: &str, _value: &BoltType) -> RedactAction {
+///         match key {
+///             "email" | "phone" => RedactAction::Mask,
+///             "password" => RedactAction::Drop,
+///             _ => RedactAction::Keep,
+///         }
+///     }
+/// }
+/// ```
+pub trait Redactor: Send + Sync {
+    /// Decide what to do with the parameter `key` holding `value`
+    fn redact(&self, key: &str, value: &BoltType) -> RedactAction;
+}
+
+/// Redactor that masks every parameter value
+///
+/// Useful when parameter names are informative but no value may leave the process.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MaskAllRedactor;
+
+impl Redactor for MaskAllRedactor {
+    fn redact(&self, _key: &str, _value: &BoltType) -> RedactAction {
+        RedactAction::Mask
+    }
+}
+
+/// Render `params` for the `db.query.parameters` attribute, applying `redactor` to each one
+///
+/// Parameters are sorted by key so the output is stable, e.g. `{age: 30, email: "***"}`.
+pub(crate) fn render_parameters(params: &BoltMap, redactor: &dyn Redactor) -> String {
+    let mut entries: Vec<_> = params
+        .value
+        .iter()
+        .map(|(key, value)| (key.value.as_str(), value))
+        .collect();
+    entries.sort_unstable_by_key(|(key, _)| *key);
+
+    let mut out = String::from("{");
+    for (key, value) in entries {
+        let rendered = match redactor.redact(key, value) {
+            RedactAction::Keep => render_value(value),
+            RedactAction::Mask => format!("{MASK:?}"),
+            RedactAction::Drop => continue,
+        };
+        if out.len() > 1 {
+            out.push_str(", ");
+        }
+        let _ = write!(out, "{key}: {rendered}");
+    }
+    out.push('}');
+    out
+}
+
+/// Cypher-like rendering of a single value
+fn render_value(value: &BoltType) -> String {
+    match value {
+        BoltType::String(s) => format!("{:?}", s.value),
+        BoltType::Boolean(b) => b.value.to_string(),
+        BoltType::Integer(i) => i.value.to_string(),
+        BoltType::Float(f) => f.value.to_string(),
+        BoltType::Null(_) => "null".to_owned(),
+        BoltType::List(list) => {
+            let items: Vec<String> = list.value.iter().map(render_value).collect();
+            format!("[{}]", items.join(", "))
+        }
+        BoltType::Map(map) => {
+            let mut entries: Vec<_> = map.value.iter().collect();
+            entries.sort_unstable_by_key(|(key, _)| key.value.as_str());
+            let items: Vec<String> = entries
+                .into_iter()
+                .map(|(key, value)| format!("{}: {}", key.value, render_value(value)))
+                .collect();
+            format!("{{{}}}", items.join(", "))
+        }
+        BoltType::Bytes(bytes) => format!("<{} bytes>", bytes.value.len()),
+        BoltType::Node(_) | BoltType::Relation(_) | BoltType::UnboundedRelation(_) => {
+            "<entity>".to_owned()
+        }
+        BoltType::Path(_) => "<path>".to_owned(),
+        BoltType::Point2D(_) | BoltType::Point3D(_) => "<point>".to_owned(),
+        _ => "<temporal>".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct KeyRedactor;
+
+    impl Redactor for KeyRedactor {
+        fn redact(&self, key: &str, _value: &BoltType) -> RedactAction {
+            match key {
+                "email" => RedactAction::Mask,
+                "password" => RedactAction::Drop,
+                _ => RedactAction::Keep,
+            }
+        }
+    }
+
+    fn params() -> BoltMap {
+        let mut params = BoltMap::default();
+        params.put("name".into(), "Alice".into());
+        params.put("age".into(), 30.into());
+        params.put("email".into(), "alice@example.com".into());
+        params.put("password".into(), "hunter2".into());
+        params.put("tags".into(), vec!["a", "b"].into());
+        params
+    }
+
+    #[test]
+    fn test_keep_mask_drop() {
+        assert_eq!(
+            render_parameters(&params(), &KeyRedactor),
+            r#"{age: 30, email: "***", name: "Alice", tags: ["a", "b"]}"#
+        );
+    }
+
+    #[test]
+    fn test_mask_all_redactor() {
+        let rendered = render_parameters(&params(), &MaskAllRedactor);
+
+        assert_eq!(
+            rendered,
+            r#"{age: "***", email: "***", name: "***", password: "***", tags: "***"}"#
+        );
+        assert!(!rendered.contains("alice@example.com"));
+    }
+
+    #[test]
+    fn test_empty_parameters() {
+        assert_eq!(
+            render_parameters(&BoltMap::default(), &MaskAllRedactor),
+            "{}"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Retry policy for connections and queries
+//!
+//! Used by [`InstrumentedGraph::connect_with_retry`](crate::InstrumentedGraph::connect_with_retry)
+//! to ride out transient network failures during startup, where only connection-level
+//! failures are retried; anything else, such as bad credentials, fails on the first attempt.
+//!
+//! [`InstrumentedGraph::run_with_retry`](crate::InstrumentedGraph::run_with_retry) uses the
+//! same policy for queries, retrying whatever [`is_retryable`] accepts, such as deadlocks and
+//! leader switches.
+
+use crate::error::{error_category, is_retryable};
+#[cfg(feature = "metrics")]
+use crate::metrics::Neo4jMetrics;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How many times to attempt a connection and how long to wait between attempts
+///
+/// The delay starts at `initial_backoff` and doubles after every failed attempt, up to
+/// `max_backoff`. With jitter, each delay is shortened by a random share of up to the
+/// `jitter` fraction, so clients that failed together don't retry in lockstep.
+///
+/// # Example
+///
+/// ```rust
+/// use otel_instrumentation_neo4jrs::RetryPolicy;
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::new()
+///     .with_max_attempts(10)
+///     .with_initial_backoff(Duration::from_millis(200))
+///     .with_max_backoff(Duration::from_secs(10))
+///     .with_jitter(0.5);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: f64,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Neo4jMetrics>>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            jitter: 0.0,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy with 5 attempts and backoff from 100ms up to 5s, without jitter
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Total number of attempts, including the first; values below 1 are treated as 1
+    #[must_use]
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry
+    #[must_use]
+    pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Upper bound on the delay between attempts
+    #[must_use]
+    pub fn with_max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Randomly shorten each delay by up to this fraction, clamped to `0.0..=1.0`
+    #[must_use]
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Count retries in the `neo4j.connect.retries` counter of `metrics`
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub fn with_metrics_instance(mut self, metrics: Arc<Neo4jMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Total number of attempts, including the first
+    #[must_use]
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Delay to wait after the given failed attempt (1-based), before jitter is applied
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Delay to actually wait after the given failed attempt, with jitter applied
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self.backoff(attempt);
+        if self.jitter <= 0.0 {
+            return backoff;
+        }
+        backoff.mul_f64(1.0 - self.jitter * random_unit())
+    }
+
+    /// Whether a connection failing with `err` after the given attempt (1-based) warrants
+    /// another one
+    pub(crate) fn should_retry(&self, err: &neo4rs::Error, attempt: u32) -> bool {
+        attempt < self.max_attempts && is_connection_failure(err)
+    }
+
+    /// Whether a query failing with `err` after the given attempt (1-based) warrants
+    /// another one
+    pub(crate) fn should_retry_query(&self, err: &neo4rs::Error, attempt: u32) -> bool {
+        attempt < self.max_attempts && is_retryable(err)
+    }
+
+    #[cfg(feature = "metrics")]
+    pub(crate) fn metrics(&self) -> Option<&Neo4jMetrics> {
+        self.metrics.as_deref()
+    }
+}
+
+/// Whether `err` is a connection-level failure that may go away on its own
+fn is_connection_failure(err: &neo4rs::Error) -> bool {
+    error_category(err) == "connection"
+}
+
+/// Random value in `0.0..=1.0`, seeded from the randomly keyed std hasher
+fn random_unit() -> f64 {
+    let bits = RandomState::new().build_hasher().finish() >> 32;
+    f64::from(u32::try_from(bits).unwrap_or(u32::MAX)) / f64::from(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retries_connection_failures_only() {
+        let policy = RetryPolicy::new();
+
+        assert!(policy.should_retry(&neo4rs::Error::ConnectionError, 1));
+        assert!(policy.should_retry(
+            &std::io::Error::from(std::io::ErrorKind::ConnectionRefused).into(),
+            1
+        ));
+        assert!(!policy.should_retry(&neo4rs::Error::AuthenticationError("denied".into()), 1));
+        assert!(!policy.should_retry(&neo4rs::Error::UnsupportedScheme("http".into()), 1));
+    }
+
+    #[test]
+    fn test_stops_after_max_attempts() {
+        let policy = RetryPolicy::new().with_max_attempts(3);
+
+        assert!(policy.should_retry(&neo4rs::Error::ConnectionError, 2));
+        assert!(!policy.should_retry(&neo4rs::Error::ConnectionError, 3));
+        assert_eq!(RetryPolicy::new().with_max_attempts(0).max_attempts(), 1);
+    }
+
+    #[test]
+    fn test_retries_retryable_queries_only() {
+        let policy = RetryPolicy::new().with_max_attempts(3);
+
+        assert!(policy.should_retry_query(&neo4rs::Error::ConnectionError, 2));
+        assert!(!policy.should_retry_query(&neo4rs::Error::ConnectionError, 3));
+        assert!(!policy.should_retry_query(&neo4rs::Error::ConversionError, 1));
+    }
+
+    #[test]
+    fn test_jitter_shortens_delay_within_bounds() {
+        let policy = RetryPolicy::new()
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_jitter(0.5);
+
+        for _ in 0..100 {
+            let delay = policy.delay(1);
+            assert!(delay >= Duration::from_millis(50), "{delay:?} below bound");
+            assert!(delay <= Duration::from_millis(100), "{delay:?} above bound");
+        }
+        assert_eq!(
+            RetryPolicy::new().delay(1),
+            RetryPolicy::new().backoff(1),
+            "no jitter by default"
+        );
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy::new()
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(350));
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+        assert_eq!(policy.backoff(40), Duration::from_millis(350));
+    }
+}
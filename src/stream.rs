@@ -0,0 +1,547 @@
+//! Instrumentation for result streams
+//!
+//! Rows are only pulled from Neo4j as a stream is consumed, so anything measured per row has
+//! to be recorded by the stream itself rather than by the method that returned it.
+
+use crate::error::{classify_error, is_retryable};
+#[cfg(feature = "metrics")]
+use crate::metrics::Neo4jMetrics;
+#[cfg(feature = "metrics")]
+use crate::query::AccessMode;
+use crate::telemetry::{record_exception, ERROR_RETRYABLE, ERROR_TYPE};
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt, TryStream, TryStreamExt};
+use neo4rs::Row;
+use opentelemetry::trace::Status;
+use std::fmt;
+use std::pin::Pin;
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Span field for the time from the start of an operation to its first row, in milliseconds
+pub(crate) const FIRST_RECORD_MS: &str = "neo4j.query.first_record_ms";
+
+/// Result stream returned by [`InstrumentedGraph::execute`](crate::InstrumentedGraph::execute)
+///
+/// Counts rows as they are pulled and, once the stream completes or is dropped, records
+/// `db.response.returned_rows` on the span of the operation that created it. With metrics
+/// enabled the count also goes to the `neo4j.query.rows` histogram and the rows' approximate
+/// size to `neo4j.query.response.bytes`. If the consumer drops the stream early, the partial
+/// count and size are recorded.
+///
+/// A stream that is consumed to the end also records `neo4j.query.stream.duration`, the time
+/// from its creation to exhaustion, complementing the dispatch-only
+/// `neo4j.query.dispatch.duration`.
+///
+/// When the first row arrives the span records `neo4j.query.first_record_ms`, the time from
+/// the start of the operation to that row, and a `neo4j.first_record` event at `DEBUG`.
+/// Compared with the stream's total time this separates server planning and execution
+/// latency from the transfer of the remaining rows.
+///
+/// The operation's span stays open while rows are pulled. If fetching a batch fails after
+/// dispatch succeeded, e.g. because the connection dropped, the span gets an error status with
+/// `error.type` and `neo4j.error.retryable`, and the error is counted on `neo4j.errors.total`.
+///
+/// Rows can be pulled one at a time with [`next`](Self::next), like a neo4rs
+/// `DetachedRowStream`, or through the [`Stream`] implementation.
+///
+/// neo4rs 0.8 discards the result summary, so update counters such as nodes created or
+/// properties set cannot be recorded.
+pub struct InstrumentedRowStream {
+    rows: BoxStream<'static, Result<Row, neo4rs::Error>>,
+    recorder: RowRecorder,
+}
+
+impl InstrumentedRowStream {
+    pub(crate) fn new<S>(
+        rows: S,
+        span: Span,
+        #[cfg(feature = "metrics")] metrics: Option<Arc<Neo4jMetrics>>,
+        operation: &'static str,
+        database: &str,
+    ) -> Self
+    where
+        S: TryStream<Ok = Row, Error = neo4rs::Error> + Send + 'static,
+    {
+        Self {
+            rows: rows.into_stream().boxed(),
+            recorder: RowRecorder {
+                span,
+                #[cfg(feature = "metrics")]
+                metrics,
+                operation,
+                database: database.to_owned(),
+                #[cfg(feature = "metrics")]
+                access_mode: None,
+                rows: 0,
+                #[cfg(feature = "metrics")]
+                bytes: 0,
+                #[cfg(feature = "metrics")]
+                started: Instant::now(),
+                operation_started: Instant::now(),
+                finished: false,
+                failed: false,
+            },
+        }
+    }
+
+    /// Measure time to the first record from `started`, the start of the operation, rather
+    /// than from the creation of the stream
+    pub(crate) fn with_operation_start(mut self, started: Instant) -> Self {
+        self.recorder.operation_started = started;
+        self
+    }
+
+    /// Label errors with the `access_mode` the statement's query was counted with
+    #[cfg(feature = "metrics")]
+    pub(crate) fn with_access_mode(mut self, access_mode: Option<AccessMode>) -> Self {
+        self.recorder.access_mode = access_mode;
+        self
+    }
+
+    /// Pull the next row, or `None` once the result is exhausted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching the next batch of rows fails
+    pub async fn next(&mut self) -> Result<Option<Row>, neo4rs::Error> {
+        StreamExt::next(self).await.transpose()
+    }
+
+    /// Number of rows pulled so far
+    #[must_use]
+    pub fn rows(&self) -> u64 {
+        self.recorder.rows
+    }
+}
+
+impl Stream for InstrumentedRowStream {
+    type Item = Result<Row, neo4rs::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let polled = this.rows.poll_next_unpin(cx);
+        match &polled {
+            Poll::Ready(Some(Ok(row))) => this.recorder.record_row(row),
+            Poll::Ready(Some(Err(e))) => this.recorder.fail(e),
+            Poll::Ready(None) => this.recorder.finish(true),
+            _ => {}
+        }
+        polled
+    }
+}
+
+impl fmt::Debug for InstrumentedRowStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InstrumentedRowStream")
+            .field("operation", &self.recorder.operation)
+            .field("database", &self.recorder.database)
+            .field("rows", &self.recorder.rows)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Records the row count of a stream exactly once, on completion or when dropped
+struct RowRecorder {
+    span: Span,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Neo4jMetrics>>,
+    operation: &'static str,
+    database: String,
+    /// Access mode of the statement, labelling errors like the statement's query count
+    #[cfg(feature = "metrics")]
+    access_mode: Option<AccessMode>,
+    rows: u64,
+    /// Summed approximate size of the rows, only computed with metrics
+    #[cfg(feature = "metrics")]
+    bytes: u64,
+    #[cfg(feature = "metrics")]
+    started: Instant,
+    operation_started: Instant,
+    finished: bool,
+    failed: bool,
+}
+
+impl RowRecorder {
+    /// Record the count; throughput is only meaningful if the stream was fully `drained`
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    fn finish(&mut self, drained: bool) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+
+        self.span.record(
+            "db.response.returned_rows",
+            i64::try_from(self.rows).unwrap_or(i64::MAX),
+        );
+
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            metrics.record_returned_rows(self.rows, Some(self.operation), &self.database);
+            metrics.record_response_bytes(self.bytes, Some(self.operation), &self.database);
+            if drained {
+                let elapsed = self.started.elapsed();
+                metrics.record_stream_duration(elapsed, Some(self.operation), &self.database);
+                metrics.record_stream_throughput(
+                    self.rows,
+                    elapsed,
+                    Some(self.operation),
+                    &self.database,
+                );
+            }
+        }
+    }
+}
+
+impl RowRecorder {
+    /// Count a pulled row, and add up its size if metrics are enabled
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    fn record_row(&mut self, row: &Row) {
+        if self.rows == 0 {
+            self.first_record();
+        }
+        self.rows += 1;
+        #[cfg(feature = "metrics")]
+        if self.metrics.is_some() {
+            self.bytes += crate::metrics::approximate_row_bytes(row);
+        }
+    }
+
+    /// Record the time from the start of the operation to the first row
+    fn first_record(&self) {
+        if self.span.is_disabled() {
+            return;
+        }
+        let first_record_ms = self.operation_started.elapsed().as_secs_f64() * 1000.0;
+        self.span.record(FIRST_RECORD_MS, first_record_ms);
+        self.span.in_scope(|| {
+            tracing::debug!(first_record_ms, "neo4j.first_record");
+        });
+    }
+
+    /// Mark the span failed and count the error, once per stream
+    fn fail(&mut self, error: &neo4rs::Error) {
+        if self.failed {
+            return;
+        }
+        self.failed = true;
+
+        self.span.record(ERROR_TYPE, classify_error(error));
+        self.span.record(ERROR_RETRYABLE, is_retryable(error));
+        record_exception(&self.span, error);
+        self.span.set_status(Status::error(error.to_string()));
+        self.span.in_scope(|| {
+            tracing::error!(
+                "Result stream failed on database {} after {} rows: {}",
+                self.database,
+                self.rows,
+                error
+            );
+        });
+
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            metrics.record_failure(
+                error,
+                Some(self.operation),
+                &self.database,
+                self.access_mode,
+            );
+        }
+    }
+}
+
+impl Drop for RowRecorder {
+    fn drop(&mut self) {
+        self.finish(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{attribute, CapturedSpans};
+    #[cfg(feature = "metrics")]
+    use crate::test_support::{histogram_points, CapturedMetrics};
+    use neo4rs::BoltList;
+    use opentelemetry::Value;
+
+    fn rows(count: usize) -> impl Stream<Item = Result<Row, neo4rs::Error>> + Send {
+        futures::stream::iter((0..count).map(|_| Ok(Row::new(BoltList::new(), BoltList::new()))))
+    }
+
+    fn execute_span() -> Span {
+        tracing::info_span!(
+            "execute",
+            db.response.returned_rows = tracing::field::Empty,
+            neo4j.query.first_record_ms = tracing::field::Empty,
+            error.type = tracing::field::Empty,
+            neo4j.error.retryable = tracing::field::Empty
+        )
+    }
+
+    /// `count` rows followed by a connection error, as when the connection drops mid-fetch
+    fn rows_then_error(count: usize) -> impl Stream<Item = Result<Row, neo4rs::Error>> + Send {
+        rows(count).chain(futures::stream::iter([Err(neo4rs::Error::ConnectionError)]))
+    }
+
+    #[cfg(feature = "metrics")]
+    fn recorded_rows(captured: &CapturedMetrics) -> Vec<u64> {
+        histogram_points::<u64>(captured, "neo4j.query.rows")
+            .iter()
+            .map(|point| point.sum)
+            .collect()
+    }
+
+    /// Number of stream durations recorded; the histogram is absent until the first one
+    #[cfg(feature = "metrics")]
+    fn stream_durations(captured: &CapturedMetrics) -> u64 {
+        if captured
+            .try_inspect("neo4j.query.stream.duration", |_| ())
+            .is_none()
+        {
+            return 0;
+        }
+        histogram_points::<f64>(captured, "neo4j.query.stream.duration")
+            .iter()
+            .map(|point| point.count)
+            .sum()
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_records_total_on_completion() {
+        let capture = CapturedSpans::install();
+        let captured = CapturedMetrics::install();
+
+        let mut stream = InstrumentedRowStream::new(
+            rows(3),
+            execute_span(),
+            Some(captured.metrics.clone()),
+            "execute",
+            "neo4j",
+        );
+        let mut consumed = 0;
+        while stream.next().await.unwrap().is_some() {
+            consumed += 1;
+        }
+
+        assert_eq!(consumed, 3);
+        assert_eq!(stream.rows(), 3);
+        assert_eq!(recorded_rows(&captured), vec![3]);
+        assert_eq!(stream_durations(&captured), 1);
+
+        // Dropping after completion must not record a second time
+        drop(stream);
+        assert_eq!(recorded_rows(&captured), vec![3]);
+        assert_eq!(
+            attribute(&capture.span("execute"), "db.response.returned_rows"),
+            Some(&Value::I64(3))
+        );
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_records_response_bytes() {
+        let _capture = CapturedSpans::install();
+        let captured = CapturedMetrics::install();
+
+        let row = || {
+            Row::new(
+                BoltList::from(vec!["name".into(), "age".into()]),
+                BoltList::from(vec!["Ann".into(), 42_i64.into()]),
+            )
+        };
+        let mut stream = InstrumentedRowStream::new(
+            futures::stream::iter([Ok(row()), Ok(row())]),
+            execute_span(),
+            Some(captured.metrics.clone()),
+            "execute",
+            "neo4j",
+        );
+        while stream.next().await.unwrap().is_some() {}
+        drop(stream);
+
+        let recorded: Vec<_> = histogram_points::<u64>(&captured, "neo4j.query.response.bytes")
+            .iter()
+            .map(|point| point.sum)
+            .collect();
+        // "name" + "Ann" + "age" + an 8 byte integer, per row
+        assert_eq!(recorded, vec![36]);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_rows_tagged_with_each_stream_database() {
+        let _capture = CapturedSpans::install();
+        let captured = CapturedMetrics::install();
+
+        for (database, count) in [("movies", 2), ("tenant_b", 4)] {
+            let mut stream = InstrumentedRowStream::new(
+                rows(count),
+                execute_span(),
+                Some(captured.metrics.clone()),
+                "execute_on",
+                database,
+            );
+            while stream.next().await.unwrap().is_some() {}
+        }
+
+        let mut recorded: Vec<_> = histogram_points::<u64>(&captured, "neo4j.query.rows")
+            .iter()
+            .map(|point| {
+                let database = point.attribute("database").expect("no database attribute");
+                (database.as_str().into_owned(), point.sum)
+            })
+            .collect();
+        recorded.sort();
+        assert_eq!(
+            recorded,
+            [("movies".to_string(), 2), ("tenant_b".to_string(), 4)]
+        );
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_records_partial_count_on_early_drop() {
+        let capture = CapturedSpans::install();
+        let captured = CapturedMetrics::install();
+
+        let mut stream = InstrumentedRowStream::new(
+            rows(5),
+            execute_span(),
+            Some(captured.metrics.clone()),
+            "execute",
+            "neo4j",
+        );
+        StreamExt::next(&mut stream).await;
+        StreamExt::next(&mut stream).await;
+        drop(stream);
+
+        assert_eq!(recorded_rows(&captured), vec![2]);
+        assert_eq!(stream_durations(&captured), 0);
+        assert_eq!(
+            attribute(&capture.span("execute"), "db.response.returned_rows"),
+            Some(&Value::I64(2))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_records_span_without_metrics() {
+        let capture = CapturedSpans::install();
+
+        let consumed = InstrumentedRowStream::new(
+            rows(4),
+            execute_span(),
+            #[cfg(feature = "metrics")]
+            None,
+            "execute",
+            "neo4j",
+        )
+        .count()
+        .await;
+
+        assert_eq!(consumed, 4);
+        assert_eq!(
+            attribute(&capture.span("execute"), "db.response.returned_rows"),
+            Some(&Value::I64(4))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_records_time_to_first_record() {
+        let capture = CapturedSpans::install();
+
+        let mut stream = InstrumentedRowStream::new(
+            rows(3),
+            execute_span(),
+            #[cfg(feature = "metrics")]
+            None,
+            "execute",
+            "neo4j",
+        )
+        .with_operation_start(
+            Instant::now()
+                .checked_sub(std::time::Duration::from_millis(50))
+                .unwrap(),
+        );
+        while stream.next().await.unwrap().is_some() {}
+        drop(stream);
+
+        let span = capture.span("execute");
+        let Some(Value::F64(first_record_ms)) = attribute(&span, FIRST_RECORD_MS) else {
+            panic!("no first record time");
+        };
+        assert!(*first_record_ms >= 50.0);
+        let events: Vec<_> = span.events.iter().map(|e| e.name.as_ref()).collect();
+        assert_eq!(events, ["neo4j.first_record"]);
+    }
+
+    #[tokio::test]
+    async fn test_mid_stream_error_marks_span_failed() {
+        let capture = CapturedSpans::install();
+        #[cfg(feature = "metrics")]
+        let captured = CapturedMetrics::install();
+
+        let mut stream = InstrumentedRowStream::new(
+            rows_then_error(2),
+            execute_span(),
+            #[cfg(feature = "metrics")]
+            Some(captured.metrics.clone()),
+            "execute",
+            "neo4j",
+        );
+        assert!(stream.next().await.unwrap().is_some());
+        assert!(stream.next().await.unwrap().is_some());
+        assert!(stream.next().await.is_err());
+        drop(stream);
+
+        let span = capture.span("execute");
+        assert!(matches!(span.status, Status::Error { .. }));
+        assert_eq!(
+            attribute(&span, "error.type"),
+            Some(&Value::from("connection"))
+        );
+        assert_eq!(
+            attribute(&span, "neo4j.error.retryable"),
+            Some(&Value::Bool(true))
+        );
+        assert_eq!(
+            attribute(&span, "exception.type"),
+            Some(&Value::from("neo4rs::Error::ConnectionError"))
+        );
+        assert_eq!(
+            attribute(&span, "db.response.returned_rows"),
+            Some(&Value::I64(2))
+        );
+        #[cfg(feature = "metrics")]
+        assert_eq!(captured.metrics.snapshot().errors_total, 1);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_mid_stream_error_labelled_with_access_mode() {
+        let _capture = CapturedSpans::install();
+        let captured = CapturedMetrics::install();
+
+        let mut stream = InstrumentedRowStream::new(
+            rows_then_error(0),
+            execute_span(),
+            Some(captured.metrics.clone()),
+            "MATCH",
+            "neo4j",
+        )
+        .with_access_mode(Some(AccessMode::Read));
+        assert!(stream.next().await.is_err());
+
+        let points = crate::test_support::sum_points(&captured, "neo4j.errors.total");
+        assert!(points[0]
+            .0
+            .contains(&opentelemetry::KeyValue::new("access_mode", "read")));
+    }
+}
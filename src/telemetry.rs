@@ -0,0 +1,848 @@
+//! Shared instrumentation settings
+//!
+//! [`TelemetryConfig`] holds the options that control what the instrumented wrappers record.
+//! An `InstrumentedGraph` owns one and hands it to every `InstrumentedTxn` it starts, so
+//! transaction spans follow the same settings as graph-level spans.
+
+use crate::error::{classify_error, exception_type, is_retryable};
+use crate::middleware::QueryMiddleware;
+use crate::redact::Redactor;
+use futures::future::Either;
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry::{Key, KeyValue, Value};
+use std::collections::HashSet;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::instrument::{WithDispatch, WithSubscriber};
+use tracing::subscriber::NoSubscriber;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Span field holding the wall-clock time immediately before the database call
+pub(crate) const OP_START_UNIX_NANOS: &str = "db.neo4j.op.start_unix_nanos";
+/// Span field holding the wall-clock time immediately after the database call
+pub(crate) const OP_END_UNIX_NANOS: &str = "db.neo4j.op.end_unix_nanos";
+/// Span field holding the measured duration of the database call in milliseconds
+pub(crate) const OP_DURATION_MS: &str = "db.neo4j.duration_ms";
+/// Span field holding the [`classify_error`] value of a failed database call
+pub(crate) const ERROR_TYPE: &str = "error.type";
+/// Span field telling whether a failed database call is worth retrying, see [`is_retryable`]
+pub(crate) const ERROR_RETRYABLE: &str = "neo4j.error.retryable";
+/// Span attribute holding the [`exception_type`] of a failed database call
+pub(crate) const EXCEPTION_TYPE: &str = "exception.type";
+/// Span attribute holding the message of a failed database call's error
+pub(crate) const EXCEPTION_MESSAGE: &str = "exception.message";
+
+/// Span attribute holding the id set with [`TelemetryConfig::with_correlation_id`]
+pub(crate) const CORRELATION_ID: &str = "correlation.id";
+
+/// Prefix of exported span names unless configured otherwise, giving e.g. `neo4j.run`
+pub const DEFAULT_SPAN_PREFIX: &str = "neo4j";
+
+/// Target of the per-operation events emitted when metric log events are enabled
+pub const METRICS_TARGET: &str = "neo4j.metrics";
+
+/// Logs a success message at `INFO`, or at `TRACE` when the config turned success logging off
+///
+/// A macro rather than a method so that events keep the calling module as their target.
+macro_rules! log_success {
+    ($config:expr, $($arg:tt)+) => {
+        if $config.quiet_success_logs {
+            tracing::trace!($($arg)+);
+        } else {
+            tracing::info!($($arg)+);
+        }
+    };
+}
+pub(crate) use log_success;
+
+/// Describes the database operation a callback is being invoked for
+#[derive(Clone, Copy, Debug)]
+pub struct OperationContext<'a> {
+    /// The operation name, e.g. `run`, `execute_on` or `txn_commit`
+    pub operation: &'a str,
+    /// The database the operation targets
+    pub database: &'a str,
+}
+
+/// Callback invoked for operations whose span will not be exported
+pub type SpanDroppedCallback = Arc<dyn Fn(&OperationContext<'_>) + Send + Sync>;
+
+/// Decides from an operation name, e.g. `MATCH Person` or `txn_run`, whether to trace it
+pub type OperationSampler = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Instrumentation settings shared between a graph and its transactions
+// Each flag is an independent opt-in, not a state machine in disguise
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Default)]
+pub struct TelemetryConfig {
+    /// Whether to record explicit start/end timestamps around each database call
+    pub(crate) record_timestamps: bool,
+    /// Invoked when an operation's span is not sampled
+    pub(crate) span_dropped: Option<SpanDroppedCallback>,
+    /// Filters parameter values before they are recorded; values are only recorded if set
+    pub(crate) redactor: Option<Arc<dyn Redactor>>,
+    /// Whether to emit a [`METRICS_TARGET`] event per operation for log-based metrics
+    pub(crate) metric_log_events: bool,
+    /// Business operation recorded as `db.neo4j.business_operation` on every span
+    pub(crate) business_operation: Option<Arc<str>>,
+    /// Whether to record statement text and parameters on spans that are not sampled
+    pub(crate) record_unsampled_statements: bool,
+    /// Whether `run_queries` emits a child span per query in the batch
+    pub(crate) batch_child_spans: bool,
+    /// Whether statement text and parameters are left off spans entirely
+    pub(crate) omit_statements: bool,
+    /// Whether `db.query.text` is held back until the operation has failed
+    pub(crate) statements_on_error_only: bool,
+    /// Logical name of the Neo4j service, recorded as `peer.service`
+    pub(crate) peer_service: Option<Arc<str>>,
+    /// Whether each transaction gets a `neo4j.transaction` span covering its lifetime
+    pub(crate) transaction_span: bool,
+    /// Whether operations run without creating any spans or events
+    pub(crate) tracing_disabled: bool,
+    /// Static attributes set on every operation and transaction span
+    pub(crate) attributes: Vec<KeyValue>,
+    /// Prefix of exported span names; [`DEFAULT_SPAN_PREFIX`] if unset
+    pub(crate) span_prefix: Option<Arc<str>>,
+    /// Database calls taking longer than this emit a `neo4j.slow_query` warning event
+    pub(crate) slow_query_threshold: Option<Duration>,
+    /// Run around every `run` driver call, outermost first
+    pub(crate) middleware: Vec<Arc<dyn QueryMiddleware>>,
+    /// Decides per query whether it gets a span; every query is traced if unset
+    pub(crate) operation_sampler: Option<OperationSampler>,
+    /// Whether success messages are logged at `TRACE` instead of `INFO`
+    pub(crate) quiet_success_logs: bool,
+    /// Request or correlation id recorded as `correlation.id` on every span
+    pub(crate) correlation_id: Option<Arc<str>>,
+    /// Parsed operations recorded by name; every parsed operation is recorded if unset
+    pub(crate) operation_allowlist: Option<Arc<HashSet<String>>>,
+}
+
+impl fmt::Debug for TelemetryConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TelemetryConfig")
+            .field("record_timestamps", &self.record_timestamps)
+            .field("span_dropped", &self.span_dropped.is_some())
+            .field("redactor", &self.redactor.is_some())
+            .field("metric_log_events", &self.metric_log_events)
+            .field("business_operation", &self.business_operation)
+            .field(
+                "record_unsampled_statements",
+                &self.record_unsampled_statements,
+            )
+            .field("batch_child_spans", &self.batch_child_spans)
+            .field("omit_statements", &self.omit_statements)
+            .field("statements_on_error_only", &self.statements_on_error_only)
+            .field("peer_service", &self.peer_service)
+            .field("transaction_span", &self.transaction_span)
+            .field("tracing_disabled", &self.tracing_disabled)
+            .field("attributes", &self.attributes)
+            .field("span_prefix", &self.span_prefix)
+            .field("slow_query_threshold", &self.slow_query_threshold)
+            .field("middleware", &self.middleware.len())
+            .field("operation_sampler", &self.operation_sampler.is_some())
+            .field("quiet_success_logs", &self.quiet_success_logs)
+            .field("correlation_id", &self.correlation_id)
+            .field("operation_allowlist", &self.operation_allowlist)
+            .finish()
+    }
+}
+
+impl TelemetryConfig {
+    /// Create a configuration with every optional feature disabled
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record explicit `db.neo4j.op.start_unix_nanos`/`db.neo4j.op.end_unix_nanos` attributes
+    #[must_use]
+    pub fn with_operation_timestamps(mut self, enabled: bool) -> Self {
+        self.record_timestamps = enabled;
+        self
+    }
+
+    /// Invoke `callback` for every operation whose span ends up not being recorded
+    #[must_use]
+    pub fn on_span_dropped(mut self, callback: SpanDroppedCallback) -> Self {
+        self.span_dropped = Some(callback);
+        self
+    }
+
+    /// Emit an `info` event under the [`METRICS_TARGET`] target for every operation
+    ///
+    /// The event carries `duration_ms`, `success`, `operation` and `database` fields so
+    /// log-based pipelines can derive the same metrics the OpenTelemetry instruments report.
+    #[must_use]
+    pub fn with_metric_log_events(mut self, enabled: bool) -> Self {
+        self.metric_log_events = enabled;
+        self
+    }
+
+    /// Record `name` as `db.neo4j.business_operation` on every operation span
+    #[must_use]
+    pub fn with_business_operation(mut self, name: &str) -> Self {
+        self.business_operation = Some(Arc::from(name));
+        self
+    }
+
+    /// Record `id` as `correlation.id` on every operation and transaction span
+    #[must_use]
+    pub fn with_correlation_id(mut self, id: impl Into<String>) -> Self {
+        self.correlation_id = Some(Arc::from(id.into()));
+        self
+    }
+
+    /// Record traced query parameters as `db.query.parameters`, filtered through `redactor`
+    #[must_use]
+    pub fn with_redactor(mut self, redactor: Box<dyn Redactor>) -> Self {
+        self.redactor = Some(Arc::from(redactor));
+        self
+    }
+
+    /// Only trace the queries whose operation name `sampler` returns `true` for
+    ///
+    /// `sampler` receives the parsed operation of traced queries (e.g. `MATCH Person`, or the
+    /// explicit name of an annotated query) and the method name for everything else
+    /// (`run`, `execute_on`, `txn_run`, ...). Queries it rejects run without any span or
+    /// event, as with [`with_tracing(false)`](Self::with_tracing); metrics are still
+    /// recorded. Transaction lifecycle, fan-out and retry spans are not sampled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use otel_instrumentation_neo4jrs::TelemetryConfig;
+    ///
+    /// // Trace every write but skip plain reads
+    /// let config = TelemetryConfig::new()
+    ///     .with_operation_sampler(Box::new(|operation| !operation.starts_with("MATCH")));
+    /// ```
+    #[must_use]
+    pub fn with_operation_sampler(
+        mut self,
+        sampler: Box<dyn Fn(&str) -> bool + Send + Sync>,
+    ) -> Self {
+        self.operation_sampler = Some(Arc::from(sampler));
+        self
+    }
+
+    /// Record only the parsed operations in `operations` by name
+    ///
+    /// Traced queries name their span and `db.operation.name` after the operation parsed
+    /// from their Cypher, e.g. `MATCH Person`. Generated Cypher can produce an unbounded
+    /// number of those; a parsed operation missing from the allowlist falls back to the
+    /// method name (`run`, `run_on`, ...) instead, on spans and in the `operation` metric
+    /// attribute. Explicit names from [`AnnotatedQuery`](crate::query::AnnotatedQuery) are
+    /// always recorded, and the operation sampler still sees the parsed operation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use otel_instrumentation_neo4jrs::TelemetryConfig;
+    /// use std::collections::HashSet;
+    ///
+    /// let config = TelemetryConfig::new().with_operation_allowlist(HashSet::from([
+    ///     "MATCH Person".to_string(),
+    ///     "MERGE Order".to_string(),
+    /// ]));
+    /// ```
+    #[must_use]
+    pub fn with_operation_allowlist(mut self, operations: HashSet<String>) -> Self {
+        self.operation_allowlist = Some(Arc::new(operations));
+        self
+    }
+
+    /// Run `middleware` around every query passed to `run` and its variants
+    ///
+    /// Middleware runs in the order it is added, see [`QueryMiddleware`].
+    #[must_use]
+    pub fn with_middleware(mut self, middleware: Arc<dyn QueryMiddleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Only record statement text and parameters on spans that will be exported
+    ///
+    /// Enabled by default: building `db.query.text`, `db.query.parameters` and the parsed
+    /// operation name is skipped for spans the sampler dropped. Disable it if another
+    /// `tracing` layer, such as a log formatter, should still see those fields.
+    #[must_use]
+    pub fn with_sampled_statements_only(mut self, enabled: bool) -> Self {
+        self.record_unsampled_statements = !enabled;
+        self
+    }
+
+    /// Emit a `neo4j.txn_run_queries.item` child span for every query of a batch
+    #[must_use]
+    pub fn with_batch_child_spans(mut self, enabled: bool) -> Self {
+        self.batch_child_spans = enabled;
+        self
+    }
+
+    /// Record statement text and parameters of traced queries at all
+    ///
+    /// Enabled by default. When disabled, traced queries are recorded like plain ones: only
+    /// the operation name reaches the span.
+    #[must_use]
+    pub fn with_statement_recording(mut self, enabled: bool) -> Self {
+        self.omit_statements = !enabled;
+        self
+    }
+
+    /// Record `db.query.text` only on spans of operations that failed
+    ///
+    /// Disabled by default. When enabled, the text of traced queries is kept back until the
+    /// result is known and recorded only if the query failed, which keeps statements off the
+    /// bulk of spans but still shows them where they help debugging. The other statement
+    /// details are recorded as usual.
+    #[must_use]
+    pub fn with_statement_recording_on_error(mut self, enabled: bool) -> Self {
+        self.statements_on_error_only = enabled;
+        self
+    }
+
+    /// Log successful operations at `INFO`
+    ///
+    /// Enabled by default. When disabled, messages such as "Transaction committed
+    /// successfully" are logged at `TRACE` instead, keeping them out of typical `INFO`
+    /// logs. Failures are logged at `ERROR` either way.
+    #[must_use]
+    pub fn with_success_logging(mut self, enabled: bool) -> Self {
+        self.quiet_success_logs = !enabled;
+        self
+    }
+
+    /// Record `name` as `peer.service` on every operation span
+    ///
+    /// `name` identifies the Neo4j deployment, not the application, whose `service.name`
+    /// belongs on the tracer's `Resource`.
+    #[must_use]
+    pub fn with_service_name(mut self, name: &str) -> Self {
+        self.peer_service = Some(Arc::from(name));
+        self
+    }
+
+    /// Group each transaction's spans under a `neo4j.transaction` span for its lifetime
+    #[must_use]
+    pub fn with_transaction_span(mut self, enabled: bool) -> Self {
+        self.transaction_span = enabled;
+        self
+    }
+
+    /// Create spans and events for operations at all; enabled by default
+    ///
+    /// When disabled, operations run with span creation suppressed regardless of the
+    /// installed subscriber, so no span is allocated for them. Metrics are still recorded.
+    #[must_use]
+    pub fn with_tracing(mut self, enabled: bool) -> Self {
+        self.tracing_disabled = !enabled;
+        self
+    }
+
+    /// Set `key = value` on every operation and transaction span
+    ///
+    /// Meant for deployment-wide tags such as `deployment.environment` or `tenant.id`. Metrics
+    /// are tagged through `MetricsBuilder::with_attribute`, or both at once through
+    /// `InstrumentedGraphBuilder::with_attribute`.
+    #[must_use]
+    pub fn with_attribute(mut self, key: impl Into<Key>, value: impl Into<Value>) -> Self {
+        self.attributes.push(KeyValue::new(key, value));
+        self
+    }
+
+    /// Name exported spans `<prefix>.<operation>`, e.g. `db.run`; defaults to
+    /// [`DEFAULT_SPAN_PREFIX`]
+    ///
+    /// An empty prefix exports the bare operation names (`run`, `start_txn`, ...). Spans of
+    /// traced queries that are named after their statement, such as `MATCH Person`, keep
+    /// that name. Only the exported OpenTelemetry name changes; the `tracing` span name seen
+    /// by other layers stays the bare operation name.
+    #[must_use]
+    pub fn with_span_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.span_prefix = Some(Arc::from(prefix.into()));
+        self
+    }
+
+    /// Emit a `neo4j.slow_query` warning event for database calls slower than `threshold`
+    ///
+    /// The event is recorded on the operation's span with `duration_ms`, `threshold_ms`,
+    /// `operation` and `database` fields. Metrics count slow queries separately, see
+    /// `MetricsBuilder::with_slow_query_threshold`.
+    #[must_use]
+    pub fn with_slow_query_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_query_threshold = Some(threshold);
+        self
+    }
+
+    /// Exported span name for `operation`, see [`with_span_prefix`](Self::with_span_prefix)
+    pub(crate) fn span_name(&self, operation: &str) -> String {
+        match self.span_prefix.as_deref().unwrap_or(DEFAULT_SPAN_PREFIX) {
+            "" => operation.to_owned(),
+            prefix => format!("{prefix}.{operation}"),
+        }
+    }
+
+    /// Set the configured static attributes and correlation id on `span`
+    pub(crate) fn apply_attributes(&self, span: &Span) {
+        for attribute in &self.attributes {
+            span.set_attribute(attribute.key.clone(), attribute.value.clone());
+        }
+        if let Some(id) = &self.correlation_id {
+            span.set_attribute(CORRELATION_ID, id.to_string());
+        }
+    }
+
+    /// Whether the parsed operation `operation` is recorded by name, see
+    /// [`with_operation_allowlist`](Self::with_operation_allowlist)
+    pub(crate) fn records_operation(&self, operation: &str) -> bool {
+        self.operation_allowlist
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(operation))
+    }
+
+    /// Whether operations create spans, see [`with_tracing`](Self::with_tracing)
+    pub(crate) fn tracing_enabled(&self) -> bool {
+        !self.tracing_disabled
+    }
+
+    /// Whether the query operation `operation` gets a span, see
+    /// [`with_operation_sampler`](Self::with_operation_sampler)
+    pub(crate) fn traces(&self, operation: &str) -> bool {
+        self.tracing_enabled()
+            && self
+                .operation_sampler
+                .as_ref()
+                .is_none_or(|sample| sample(operation))
+    }
+
+    /// Whether statement details should be recorded on `span`
+    pub(crate) fn should_record_statement(&self, span: &Span) -> bool {
+        !self.omit_statements && (self.record_unsampled_statements || is_sampled(span))
+    }
+
+    /// Invoke the span-dropped callback if the current span will not be exported
+    ///
+    /// A span counts as dropped when `tracing` disabled it outright or when the
+    /// OpenTelemetry sampler decided not to sample it.
+    pub(crate) fn notify_if_dropped(&self, operation: &str, database: &str) {
+        let Some(callback) = &self.span_dropped else {
+            return;
+        };
+        // Spans are off on purpose, not dropped by a sampler
+        if self.tracing_disabled {
+            return;
+        }
+
+        if !is_sampled(&Span::current()) {
+            callback(&OperationContext {
+                operation,
+                database,
+            });
+        }
+    }
+
+    /// Await the database call `fut`, recording its duration on the current span
+    ///
+    /// `db.neo4j.duration_ms` is always recorded, so backends that don't expose span
+    /// duration as a field can still filter and sort on it; the configured static attributes
+    /// are set here too. Wall-clock timestamps around the call, a metric log event for it and
+    /// a `neo4j.slow_query` warning past the slow query threshold are emitted as well if
+    /// enabled. A failed call also records `error.type`, `neo4j.error.retryable` and the
+    /// `exception.*` attributes.
+    ///
+    /// Returns the call's output along with the recorded duration in milliseconds, which
+    /// callers attach to their own log events.
+    pub(crate) async fn timed<T, F>(
+        &self,
+        operation: &str,
+        database: &str,
+        fut: F,
+    ) -> (F::Output, f64)
+    where
+        F: Future<Output = Result<T, neo4rs::Error>>,
+    {
+        let span = Span::current();
+        self.apply_attributes(&span);
+        if self.record_timestamps {
+            span.record(OP_START_UNIX_NANOS, unix_nanos());
+        }
+
+        let started = Instant::now();
+        let output = fut.await;
+        let elapsed = started.elapsed();
+        let duration_ms = elapsed.as_secs_f64() * 1000.0;
+        span.record(OP_DURATION_MS, duration_ms);
+        if let Some(threshold) = self.slow_query_threshold.filter(|t| elapsed > *t) {
+            tracing::warn!(
+                duration_ms,
+                threshold_ms = threshold.as_secs_f64() * 1000.0,
+                operation,
+                database,
+                "neo4j.slow_query"
+            );
+        }
+        if let Err(e) = &output {
+            span.record(ERROR_TYPE, classify_error(e));
+            span.record(ERROR_RETRYABLE, is_retryable(e));
+            record_exception(&span, e);
+        }
+
+        if self.record_timestamps {
+            span.record(OP_END_UNIX_NANOS, unix_nanos());
+        }
+        if self.metric_log_events {
+            tracing::info!(
+                target: METRICS_TARGET,
+                duration_ms,
+                success = output.is_ok(),
+                operation,
+                database,
+                "neo4j operation"
+            );
+        }
+        (output, duration_ms)
+    }
+}
+
+/// Wrap `fut` so that every span and event it creates is suppressed unless `enabled`
+///
+/// The spans of `#[instrument]`ed async functions are created on their first poll, so
+/// running such a future under a [`NoSubscriber`] dispatcher skips them entirely; the
+/// subscriber never sees their metadata and nothing is allocated for them.
+pub(crate) fn traced<F: Future>(enabled: bool, fut: F) -> Either<F, WithDispatch<F>> {
+    if enabled {
+        Either::Left(fut)
+    } else {
+        Either::Right(fut.with_subscriber(NoSubscriber::default()))
+    }
+}
+
+/// Whether `span` is enabled and sampled, i.e. will be exported
+pub(crate) fn is_sampled(span: &Span) -> bool {
+    !span.is_disabled() && span.context().span().span_context().is_sampled()
+}
+
+/// Set the `exception.type` and `exception.message` attributes for `err` on `span`
+///
+/// These are set through the OpenTelemetry span rather than as `tracing` fields, so spans
+/// don't need to declare them up front. `neo4rs` errors carry no backtrace, so
+/// `exception.stacktrace` is never recorded.
+pub(crate) fn record_exception(span: &Span, err: &neo4rs::Error) {
+    span.set_attribute(EXCEPTION_TYPE, exception_type(err).to_owned());
+    span.set_attribute(EXCEPTION_MESSAGE, err.to_string());
+}
+
+/// Current wall-clock time as nanoseconds since the Unix epoch
+///
+/// Recorded as `i64` so exporters keep it numeric. Saturates instead of panicking if the
+/// clock is set before the epoch or far in the future.
+fn unix_nanos() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_nanos()).unwrap_or(i64::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{attribute, CapturedSpans};
+    use opentelemetry::Value;
+    use opentelemetry_sdk::trace::SpanData;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use tracing::field::{Empty, Field, Visit};
+    use tracing::Instrument;
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    async fn sleep_ok() -> Result<(), neo4rs::Error> {
+        tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        Ok(())
+    }
+
+    async fn run_in_span(config: &TelemetryConfig) -> SpanData {
+        let capture = CapturedSpans::install();
+
+        let span = tracing::info_span!(
+            "op",
+            db.neo4j.op.start_unix_nanos = Empty,
+            db.neo4j.op.end_unix_nanos = Empty,
+            db.neo4j.duration_ms = Empty
+        );
+        config
+            .timed("run", "neo4j", sleep_ok())
+            .instrument(span)
+            .await
+            .0
+            .unwrap();
+
+        capture.span("op")
+    }
+
+    fn float_attribute(span: &SpanData, key: &str) -> Option<f64> {
+        match attribute(span, key) {
+            Some(Value::F64(v)) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn int_attribute(span: &SpanData, key: &str) -> Option<i64> {
+        match attribute(span, key) {
+            Some(Value::I64(v)) => Some(*v),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_operation_sampler_decides_per_operation() {
+        let config = TelemetryConfig::new()
+            .with_operation_sampler(Box::new(|operation| operation != "txn_run"));
+
+        assert!(config.traces("txn_execute"));
+        assert!(!config.traces("txn_run"));
+        assert!(TelemetryConfig::default().traces("txn_run"));
+        assert!(!TelemetryConfig::new()
+            .with_tracing(false)
+            .traces("txn_execute"));
+    }
+
+    #[test]
+    fn test_span_name_prefix() {
+        assert_eq!(TelemetryConfig::new().span_name("run"), "neo4j.run");
+        assert_eq!(
+            TelemetryConfig::new()
+                .with_span_prefix("orders")
+                .span_name("start_txn"),
+            "orders.start_txn"
+        );
+        assert_eq!(
+            TelemetryConfig::new().with_span_prefix("").span_name("run"),
+            "run"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_operation_timestamps_recorded_when_enabled() {
+        let config = TelemetryConfig::new().with_operation_timestamps(true);
+        let span = run_in_span(&config).await;
+
+        let start = int_attribute(&span, OP_START_UNIX_NANOS).expect("start timestamp missing");
+        let end = int_attribute(&span, OP_END_UNIX_NANOS).expect("end timestamp missing");
+        assert!(end >= start);
+    }
+
+    #[tokio::test]
+    async fn test_operation_timestamps_absent_by_default() {
+        let span = run_in_span(&TelemetryConfig::default()).await;
+
+        assert!(int_attribute(&span, OP_START_UNIX_NANOS).is_none());
+        assert!(int_attribute(&span, OP_END_UNIX_NANOS).is_none());
+    }
+
+    /// Collects the fields of events emitted under [`METRICS_TARGET`]
+    #[derive(Clone, Default)]
+    struct MetricEvents(Arc<Mutex<Vec<HashMap<String, String>>>>);
+
+    impl<S: tracing::Subscriber> Layer<S> for MetricEvents {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            if event.metadata().target() != METRICS_TARGET {
+                return;
+            }
+            let mut fields = FieldMap::default();
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+    }
+
+    #[derive(Default)]
+    struct FieldMap(HashMap<String, String>);
+
+    impl Visit for FieldMap {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0.insert(field.name().to_owned(), format!("{value:?}"));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_owned(), value.to_owned());
+        }
+    }
+
+    async fn metric_events(config: &TelemetryConfig) -> Vec<HashMap<String, String>> {
+        let events = MetricEvents::default();
+        let _guard = tracing_subscriber::registry()
+            .with(events.clone())
+            .set_default();
+
+        config
+            .timed("run_on", "movies", sleep_ok())
+            .await
+            .0
+            .unwrap();
+        let _ = config
+            .timed("txn_commit", "default", async {
+                Err::<(), _>(neo4rs::Error::ConnectionError)
+            })
+            .await;
+
+        let collected = events.0.lock().unwrap().clone();
+        collected
+    }
+
+    #[tokio::test]
+    async fn test_metric_log_events_emitted_when_enabled() {
+        let config = TelemetryConfig::new().with_metric_log_events(true);
+        let events = metric_events(&config).await;
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["operation"], "run_on");
+        assert_eq!(events[0]["database"], "movies");
+        assert_eq!(events[0]["success"], "true");
+        assert!(events[0]["duration_ms"].parse::<f64>().unwrap() >= 1.0);
+        assert_eq!(events[1]["operation"], "txn_commit");
+        assert_eq!(events[1]["success"], "false");
+    }
+
+    #[tokio::test]
+    async fn test_metric_log_events_off_by_default() {
+        assert!(metric_events(&TelemetryConfig::default()).await.is_empty());
+    }
+
+    /// Collects the fields of `WARN` events
+    #[derive(Clone, Default)]
+    struct WarnEvents(Arc<Mutex<Vec<HashMap<String, String>>>>);
+
+    impl<S: tracing::Subscriber> Layer<S> for WarnEvents {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            if *event.metadata().level() != tracing::Level::WARN {
+                return;
+            }
+            let mut fields = FieldMap::default();
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+    }
+
+    async fn warn_events(config: &TelemetryConfig) -> Vec<HashMap<String, String>> {
+        let events = WarnEvents::default();
+        let _guard = tracing_subscriber::registry()
+            .with(events.clone())
+            .set_default();
+
+        config
+            .timed("run_on", "movies", sleep_ok())
+            .await
+            .0
+            .unwrap();
+
+        let collected = events.0.lock().unwrap().clone();
+        collected
+    }
+
+    #[tokio::test]
+    async fn test_slow_query_event_past_threshold() {
+        let config = TelemetryConfig::new().with_slow_query_threshold(Duration::ZERO);
+        let events = warn_events(&config).await;
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["message"], "neo4j.slow_query");
+        assert_eq!(events[0]["operation"], "run_on");
+        assert_eq!(events[0]["database"], "movies");
+        assert!(events[0]["duration_ms"].parse::<f64>().unwrap() >= 1.0);
+        assert_eq!(events[0]["threshold_ms"], "0.0");
+    }
+
+    /// Collects the level of every event
+    #[derive(Clone, Default)]
+    struct EventLevels(Arc<Mutex<Vec<tracing::Level>>>);
+
+    impl<S: tracing::Subscriber> Layer<S> for EventLevels {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            self.0.lock().unwrap().push(*event.metadata().level());
+        }
+    }
+
+    fn success_log_level(config: &TelemetryConfig) -> Vec<tracing::Level> {
+        let levels = EventLevels::default();
+        let _guard = tracing_subscriber::registry()
+            .with(levels.clone())
+            .set_default();
+
+        log_success!(config, "Query run successfully");
+
+        let collected = levels.0.lock().unwrap().clone();
+        collected
+    }
+
+    #[test]
+    fn test_success_logging_level() {
+        assert_eq!(
+            success_log_level(&TelemetryConfig::default()),
+            [tracing::Level::INFO]
+        );
+        assert_eq!(
+            success_log_level(&TelemetryConfig::new().with_success_logging(false)),
+            [tracing::Level::TRACE]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slow_query_event_absent_under_threshold() {
+        let config = TelemetryConfig::new().with_slow_query_threshold(Duration::from_secs(30));
+        assert!(warn_events(&config).await.is_empty());
+        assert!(warn_events(&TelemetryConfig::default()).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_duration_always_recorded() {
+        let span = run_in_span(&TelemetryConfig::default()).await;
+
+        let duration = float_attribute(&span, OP_DURATION_MS).expect("duration missing");
+        assert!(duration >= 1.0, "expected at least 1ms, got {duration}");
+    }
+
+    #[tokio::test]
+    async fn test_error_type_recorded_on_failure() {
+        let capture = CapturedSpans::install();
+
+        let span = tracing::info_span!(
+            "op",
+            db.neo4j.duration_ms = Empty,
+            error.type = Empty,
+            neo4j.error.retryable = Empty
+        );
+        let _ = TelemetryConfig::default()
+            .timed("run", "neo4j", async {
+                Err::<(), _>(std::io::Error::from(std::io::ErrorKind::TimedOut).into())
+            })
+            .instrument(span)
+            .await;
+
+        let span = capture.span("op");
+        assert_eq!(attribute(&span, ERROR_TYPE), Some(&Value::from("timeout")));
+        assert_eq!(attribute(&span, ERROR_RETRYABLE), Some(&Value::Bool(true)));
+        assert_eq!(
+            attribute(&span, EXCEPTION_TYPE),
+            Some(&Value::from("neo4rs::Error::IOError"))
+        );
+        assert!(attribute(&span, EXCEPTION_MESSAGE).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_error_type_absent_on_success() {
+        let capture = CapturedSpans::install();
+
+        let span = tracing::info_span!("op", db.neo4j.duration_ms = Empty, error.type = Empty);
+        TelemetryConfig::default()
+            .timed("run", "neo4j", sleep_ok())
+            .instrument(span)
+            .await
+            .0
+            .unwrap();
+
+        let span = capture.span("op");
+        assert_eq!(attribute(&span, ERROR_TYPE), None);
+        assert_eq!(attribute(&span, EXCEPTION_TYPE), None);
+    }
+}
@@ -0,0 +1,205 @@
+//! Span and metric capture helpers shared by the unit tests
+
+#[cfg(feature = "metrics")]
+use crate::metrics::{MetricsBuilder, Neo4jMetrics};
+use crate::testing::SpanCapture;
+#[cfg(feature = "metrics")]
+use opentelemetry::metrics::{Meter, MeterProvider as _};
+#[cfg(feature = "metrics")]
+use opentelemetry::KeyValue;
+use opentelemetry::Value;
+#[cfg(feature = "metrics")]
+use opentelemetry_sdk::metrics::data::{
+    AggregatedMetrics, Histogram, MetricData, ResourceMetrics, ScopeMetrics,
+};
+#[cfg(feature = "metrics")]
+use opentelemetry_sdk::metrics::{InMemoryMetricExporter, PeriodicReader, SdkMeterProvider};
+use opentelemetry_sdk::trace::{Sampler, SpanData};
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
+
+/// [`SpanCapture`] with lookups that panic instead of returning `None`
+pub(crate) struct CapturedSpans(SpanCapture);
+
+impl CapturedSpans {
+    pub(crate) fn install() -> Self {
+        Self(SpanCapture::install())
+    }
+
+    pub(crate) fn install_with_sampler(sampler: Sampler) -> Self {
+        Self(SpanCapture::install_with_sampler(sampler))
+    }
+
+    /// All spans finished so far
+    pub(crate) fn spans(&self) -> Vec<SpanData> {
+        self.0.spans()
+    }
+
+    /// The first finished span with the given name
+    pub(crate) fn span(&self, name: &str) -> SpanData {
+        self.0.assert_has_span(name)
+    }
+}
+
+/// Look up an attribute on a finished span
+pub(crate) fn attribute<'a>(span: &'a SpanData, key: &str) -> Option<&'a Value> {
+    SpanCapture::attribute(span, key)
+}
+
+/// A `Neo4jMetrics` whose instruments export into an in-memory exporter
+#[cfg(feature = "metrics")]
+pub(crate) struct CapturedMetrics {
+    provider: SdkMeterProvider,
+    exporter: InMemoryMetricExporter,
+    pub(crate) metrics: Arc<Neo4jMetrics>,
+}
+
+#[cfg(feature = "metrics")]
+impl CapturedMetrics {
+    pub(crate) fn install() -> Self {
+        Self::install_with(Neo4jMetrics::new)
+    }
+
+    pub(crate) fn install_with(build: impl FnOnce(&Meter) -> Neo4jMetrics) -> Self {
+        let exporter = InMemoryMetricExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(exporter.clone()).build())
+            .build();
+        let metrics = Arc::new(build(&provider.meter("test")));
+
+        Self {
+            provider,
+            exporter,
+            metrics,
+        }
+    }
+
+    /// Like [`install_with`](Self::install_with), with metrics built by the
+    /// [`MetricsBuilder`] `configure` returns
+    pub(crate) fn install_configured(
+        configure: impl FnOnce(MetricsBuilder) -> MetricsBuilder,
+    ) -> Self {
+        Self::install_with(|meter| {
+            let builder = configure(MetricsBuilder::new().with_meter(meter.clone()));
+            (*builder.build().expect("metrics are enabled")).clone()
+        })
+    }
+
+    /// Inspect the data exported so far for the metric with the given name
+    pub(crate) fn inspect<R>(&self, name: &str, f: impl FnOnce(&AggregatedMetrics) -> R) -> R {
+        self.try_inspect(name, f)
+            .unwrap_or_else(|| panic!("no metric named {name}"))
+    }
+
+    /// Like [`inspect`](Self::inspect), but `None` if nothing was recorded for the metric
+    pub(crate) fn try_inspect<R>(
+        &self,
+        name: &str,
+        f: impl FnOnce(&AggregatedMetrics) -> R,
+    ) -> Option<R> {
+        self.provider.force_flush().unwrap();
+        let finished = self.exporter.get_finished_metrics().unwrap();
+        let metric = finished
+            .iter()
+            .flat_map(ResourceMetrics::scope_metrics)
+            .flat_map(ScopeMetrics::metrics)
+            .filter(|m| m.name() == name)
+            .last()?;
+        Some(f(metric.data()))
+    }
+
+    /// The unit declared for the metric with the given name
+    pub(crate) fn unit(&self, name: &str) -> String {
+        self.provider.force_flush().unwrap();
+        self.exporter
+            .get_finished_metrics()
+            .unwrap()
+            .iter()
+            .flat_map(ResourceMetrics::scope_metrics)
+            .flat_map(ScopeMetrics::metrics)
+            .find(|m| m.name() == name)
+            .map_or_else(
+                || panic!("no metric named {name}"),
+                |m| m.unit().to_string(),
+            )
+    }
+}
+
+/// A histogram data point, detached from the exported batch it came from
+#[cfg(feature = "metrics")]
+pub(crate) struct HistogramPoint<T> {
+    pub(crate) attributes: Vec<KeyValue>,
+    pub(crate) count: u64,
+    pub(crate) sum: T,
+    pub(crate) bounds: Vec<f64>,
+}
+
+#[cfg(feature = "metrics")]
+impl<T> HistogramPoint<T> {
+    /// The value of the attribute with the given key, if present
+    pub(crate) fn attribute(&self, key: &str) -> Option<&Value> {
+        self.attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| &kv.value)
+    }
+}
+
+/// The value types histograms are recorded in
+#[cfg(feature = "metrics")]
+pub(crate) trait HistogramValue: Copy {
+    fn histogram(data: &AggregatedMetrics) -> Option<&Histogram<Self>>;
+}
+
+#[cfg(feature = "metrics")]
+impl HistogramValue for u64 {
+    fn histogram(data: &AggregatedMetrics) -> Option<&Histogram<Self>> {
+        match data {
+            AggregatedMetrics::U64(MetricData::Histogram(histogram)) => Some(histogram),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl HistogramValue for f64 {
+    fn histogram(data: &AggregatedMetrics) -> Option<&Histogram<Self>> {
+        match data {
+            AggregatedMetrics::F64(MetricData::Histogram(histogram)) => Some(histogram),
+            _ => None,
+        }
+    }
+}
+
+/// Every data point of the `T` histogram with the given name
+#[cfg(feature = "metrics")]
+pub(crate) fn histogram_points<T: HistogramValue>(
+    captured: &CapturedMetrics,
+    name: &str,
+) -> Vec<HistogramPoint<T>> {
+    captured.inspect(name, |data| {
+        let histogram = T::histogram(data).unwrap_or_else(|| panic!("unexpected {name} type"));
+        histogram
+            .data_points()
+            .map(|point| HistogramPoint {
+                attributes: point.attributes().cloned().collect(),
+                count: point.count(),
+                sum: point.sum(),
+                bounds: point.bounds().collect(),
+            })
+            .collect()
+    })
+}
+
+/// Attributes and value of every data point of the `u64` counter with the given name
+#[cfg(feature = "metrics")]
+pub(crate) fn sum_points(captured: &CapturedMetrics, name: &str) -> Vec<(Vec<KeyValue>, u64)> {
+    captured.inspect(name, |data| {
+        let AggregatedMetrics::U64(MetricData::Sum(sum)) = data else {
+            panic!("unexpected {name} type");
+        };
+        sum.data_points()
+            .map(|point| (point.attributes().cloned().collect(), point.value()))
+            .collect()
+    })
+}
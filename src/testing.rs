@@ -0,0 +1,202 @@
+//! Span capture for tests of code built on this crate
+//!
+//! Enabled by the `test-util` feature, usually from `[dev-dependencies]`:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! otel-instrumentation-neo4jrs = { version = "0.2", features = ["test-util"] }
+//! ```
+//!
+//! [`SpanCapture`] routes the `tracing` spans of the current thread into an in-memory
+//! OpenTelemetry exporter, so tests can assert on the spans an [`InstrumentedGraph`]
+//! records without setting up a pipeline of their own.
+//!
+//! [`InstrumentedGraph`]: crate::InstrumentedGraph
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::Value;
+use opentelemetry_sdk::trace::{
+    InMemorySpanExporter, Sampler, SdkTracerProvider, SpanData, Tracer,
+};
+use tracing_subscriber::prelude::*;
+
+/// Captures the spans recorded on the current thread while it is alive
+///
+/// Installs a thread-local default subscriber, so use a current-thread runtime (the
+/// `#[tokio::test]` default) for spans of async code to be captured. Dropping the capture
+/// restores the previous subscriber.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use otel_instrumentation_neo4jrs::testing::SpanCapture;
+/// # use otel_instrumentation_neo4jrs::InstrumentedGraph;
+///
+/// # async fn example(graph: InstrumentedGraph) -> Result<(), neo4rs::Error> {
+/// let capture = SpanCapture::install();
+/// graph.run(neo4rs::query("RETURN 1")).await?;
+///
+/// let span = capture.assert_has_operation("run");
+/// assert_eq!(
+///     SpanCapture::attribute(&span, "db.system.name").map(|v| v.as_str()),
+///     Some("neo4j".into())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub struct SpanCapture {
+    provider: SdkTracerProvider,
+    exporter: InMemorySpanExporter,
+    _guard: tracing::subscriber::DefaultGuard,
+}
+
+impl SpanCapture {
+    /// Start capturing the spans of the current thread
+    #[must_use]
+    pub fn install() -> Self {
+        Self::install_with_sampler(Sampler::AlwaysOn)
+    }
+
+    /// Start capturing the spans of the current thread that `sampler` samples
+    #[must_use]
+    pub fn install_with_sampler(sampler: Sampler) -> Self {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_sampler(sampler)
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_opentelemetry::layer()
+                .with_tracer(provider.tracer("otel-instrumentation-neo4jrs")),
+        );
+        let guard = tracing::subscriber::set_default(subscriber);
+
+        Self {
+            provider,
+            exporter,
+            _guard: guard,
+        }
+    }
+
+    /// All spans finished since the capture was installed or last [`reset`](Self::reset)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the in-memory exporter cannot be read
+    #[must_use]
+    pub fn spans(&self) -> Vec<SpanData> {
+        let _ = self.provider.force_flush();
+        self.exporter
+            .get_finished_spans()
+            .expect("in-memory span exporter is readable")
+    }
+
+    /// An OpenTelemetry tracer whose spans are captured alongside the instrumented ones
+    ///
+    /// Use it for application spans started through the OpenTelemetry API, e.g. a parent
+    /// context passed to [`InstrumentedGraph::run_in_context`].
+    ///
+    /// [`InstrumentedGraph::run_in_context`]: crate::InstrumentedGraph::run_in_context
+    #[must_use]
+    pub fn tracer(&self, name: &'static str) -> Tracer {
+        self.provider.tracer(name)
+    }
+
+    /// Forget the spans captured so far, e.g. after test setup
+    pub fn reset(&self) {
+        self.exporter.reset();
+    }
+
+    /// The first finished span with the given name, e.g. `neo4j.run`
+    #[must_use]
+    pub fn span(&self, name: &str) -> Option<SpanData> {
+        self.spans().into_iter().find(|s| s.name == name)
+    }
+
+    /// Assert that a finished span recorded `db.operation.name = operation` and return it
+    ///
+    /// # Panics
+    ///
+    /// Panics, listing the operations that were recorded, if there is no such span
+    // Often called for the assertion alone
+    #[allow(clippy::must_use_candidate)]
+    #[track_caller]
+    pub fn assert_has_operation(&self, operation: &str) -> SpanData {
+        let spans = self.spans();
+        if let Some(span) = spans.iter().find(|s| {
+            Self::attribute(s, "db.operation.name").is_some_and(|v| v.as_str() == operation)
+        }) {
+            return span.clone();
+        }
+
+        let recorded: Vec<_> = spans
+            .iter()
+            .filter_map(|s| Self::attribute(s, "db.operation.name"))
+            .map(Value::as_str)
+            .collect();
+        panic!("no span with db.operation.name = {operation:?}, recorded: {recorded:?}");
+    }
+
+    /// Assert that a finished span has the given name and return it
+    ///
+    /// # Panics
+    ///
+    /// Panics, listing the span names that were recorded, if there is no such span
+    // Often called for the assertion alone
+    #[allow(clippy::must_use_candidate)]
+    #[track_caller]
+    pub fn assert_has_span(&self, name: &str) -> SpanData {
+        let spans = self.spans();
+        if let Some(span) = spans.iter().find(|s| s.name == name) {
+            return span.clone();
+        }
+
+        let recorded: Vec<_> = spans.iter().map(|s| s.name.as_ref()).collect();
+        panic!("no span named {name:?}, recorded: {recorded:?}");
+    }
+
+    /// Look up an attribute on a finished span
+    #[must_use]
+    pub fn attribute<'a>(span: &'a SpanData, key: &str) -> Option<&'a Value> {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| &kv.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InstrumentedGraph;
+
+    #[tokio::test]
+    async fn test_captures_operation_spans() {
+        let capture = SpanCapture::install();
+        let graph = InstrumentedGraph::connect("bolt://127.0.0.1:1", "neo4j", "password")
+            .await
+            .unwrap();
+        capture.reset();
+
+        // Nothing listens on port 1, but the attempt still records its span
+        assert!(graph.start_txn().await.is_err());
+
+        let span = capture.assert_has_operation("start_transaction");
+        assert_eq!(span.name, "neo4j.start_txn");
+        assert_eq!(
+            SpanCapture::attribute(&span, "db.system.name"),
+            Some(&Value::from("neo4j"))
+        );
+        assert!(capture.span("neo4j.connect").is_none());
+
+        capture.reset();
+        assert!(capture.spans().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "no span with db.operation.name = \"run\"")]
+    fn test_assert_has_operation_panics_when_missing() {
+        let capture = SpanCapture::install();
+        capture.assert_has_operation("run");
+    }
+}
@@ -1,35 +1,151 @@
+use crate::error::classify_error;
+#[cfg(feature = "metrics")]
 use crate::metrics::{Neo4jMetrics, OperationTimer};
+use crate::telemetry::{log_success, record_exception, traced, TelemetryConfig};
 use neo4rs::{Query, RowStream, Txn};
+use opentelemetry::trace::{SpanContext, TraceContextExt};
 use std::sync::Arc;
-use tracing::{debug, error, info, instrument};
+use tracing::field::Empty;
+use tracing::span::Id;
+use tracing::{debug, error, info, instrument, warn, Instrument, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Most query spans a commit span links to, matching the SDK's default span link limit
+const MAX_QUERY_LINKS: usize = 128;
 
 /// An instrumented wrapper around Neo4j transaction
+///
+/// A neo4rs transaction is bound to one database when it begins, so there are no per-query
+/// `run_on`/`execute_on` variants here; start the transaction with
+/// [`InstrumentedGraph::start_txn_on`](crate::InstrumentedGraph::start_txn_on) to target a
+/// named database. That name is passed to [`new`](Self::new) and recorded on every span and
+/// metric of the transaction.
+///
+/// Dropping a transaction without calling [`commit`](Self::commit) or
+/// [`rollback`](Self::rollback) makes the server roll it back silently. The wrapper reports
+/// it instead: a `txn.dropped_uncommitted` warning event is emitted (on the transaction span,
+/// if there is one) and, with metrics enabled, the transaction is recorded as a rollback with
+/// `outcome = "dropped"`.
 pub struct InstrumentedTxn {
     inner: Txn,
     server_address: String,
     server_port: u16,
+    #[cfg(feature = "metrics")]
     metrics: Option<Arc<Neo4jMetrics>>,
-    transaction_timer: Option<OperationTimer>,
+    config: Arc<TelemetryConfig>,
+    completion: Completion,
+    server_version: Option<String>,
+    tls_enabled: bool,
+    peer_address: Option<String>,
+    database: String,
+    transaction_span: Option<Span>,
+    /// Contexts of the query spans run so far, linked from the commit span
+    query_spans: Vec<SpanContext>,
 }
 
 impl InstrumentedTxn {
     /// Create a new instrumented transaction wrapper
+    ///
+    /// `database` is the database the transaction runs against, recorded as `db.namespace`
+    /// and as the `database` attribute of its metrics. Metrics are recorded once they are
+    /// attached with `with_metrics`.
     #[must_use]
     pub fn new(
         inner: Txn,
         server_address: String,
         server_port: u16,
-        metrics: Option<Arc<Neo4jMetrics>>,
+        database: String,
+        config: Arc<TelemetryConfig>,
     ) -> Self {
-        // Start transaction timer if metrics are enabled
-        let transaction_timer = metrics.as_ref().map(|_| OperationTimer::start());
+        let completion = Completion {
+            completed: false,
+            database: database.clone(),
+            transaction_span: None,
+            tracing_enabled: config.tracing_enabled(),
+            query_count: 0,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            #[cfg(feature = "metrics")]
+            transaction_timer: None,
+        };
 
         Self {
             inner,
             server_address,
             server_port,
-            metrics,
-            transaction_timer,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            config,
+            completion,
+            server_version: None,
+            tls_enabled: false,
+            peer_address: None,
+            database,
+            transaction_span: None,
+            query_spans: Vec::new(),
+        }
+    }
+
+    /// Queries run so far, recorded as `neo4j.transaction.query_count` on commit and rollback
+    fn recorded_query_count(&self) -> i64 {
+        i64::try_from(self.completion.query_count).unwrap_or(i64::MAX)
+    }
+
+    /// Record the transaction's queries, duration and outcome on `metrics`
+    ///
+    /// The transaction's duration is timed from this call.
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub fn with_metrics(mut self, metrics: Arc<Neo4jMetrics>) -> Self {
+        self.completion.transaction_timer = Some(OperationTimer::start());
+        self.completion.metrics = Some(metrics.clone());
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Record `db.system.version` on this transaction's spans
+    pub(crate) fn with_server_version(mut self, version: Option<String>) -> Self {
+        self.server_version = version;
+        self
+    }
+
+    /// Record whether the connection is encrypted in `network.transport.encrypted`
+    pub(crate) fn with_tls_enabled(mut self, enabled: bool) -> Self {
+        self.tls_enabled = enabled;
+        self
+    }
+
+    /// Record the graph's resolved peer as `network.peer.address`/`network.peer.port`
+    pub(crate) fn with_peer_address(mut self, address: Option<String>) -> Self {
+        self.peer_address = address;
+        self
+    }
+
+    /// `network.peer.port`, recorded only alongside a resolved `network.peer.address`
+    fn peer_port(&self) -> Option<i64> {
+        self.peer_address
+            .as_ref()
+            .map(|_| i64::from(self.server_port))
+    }
+
+    /// Parent this transaction's spans and lifecycle events under `span`
+    pub(crate) fn with_transaction_span(mut self, span: Option<Span>) -> Self {
+        self.completion.transaction_span.clone_from(&span);
+        self.transaction_span = span;
+        self
+    }
+
+    /// Parent for operation spans: the transaction span if there is one, else the current span
+    fn parent_span(&self) -> Option<Id> {
+        self.transaction_span
+            .as_ref()
+            .map_or_else(|| Span::current().id(), Span::id)
+    }
+
+    /// Emit `event` on the transaction span, if there is one
+    fn lifecycle_event(&self, event: &str, operation: &str) {
+        if let Some(span) = &self.transaction_span {
+            info!(parent: span, db.operation.name = operation, "{event}");
         }
     }
 
@@ -38,40 +154,81 @@ impl InstrumentedTxn {
     /// # Errors
     ///
     /// Returns an error if the query execution fails
+    pub async fn execute(&mut self, query: Query) -> Result<RowStream, neo4rs::Error> {
+        traced(self.config.traces("txn_execute"), self.execute_inner(query)).await
+    }
+
     #[instrument(
+        name = "execute",
         skip(self, query),
+        parent = self.parent_span(),
         fields(
+            otel.name = %self.config.span_name("execute"),
             otel.kind = "CLIENT",
             db.system.name = "neo4j",
+            db.neo4j.tx.type = "explicit",
+            db.neo4j.business_operation = self.config.business_operation.as_deref(),
+            peer.service = self.config.peer_service.as_deref(),
+            db.system.version = self.server_version.as_deref(),
             server.address = %self.server_address,
             server.port = %self.server_port,
-            db.namespace = "default",
-            db.operation.name = "txn_execute"
+            network.transport.encrypted = self.tls_enabled,
+            network.peer.address = self.peer_address.as_deref(),
+            network.peer.port = self.peer_port(),
+            db.namespace = self.database.as_str(),
+            db.operation.name = "txn_execute",
+            db.neo4j.op.start_unix_nanos = Empty,
+            db.neo4j.op.end_unix_nanos = Empty,
+            db.neo4j.duration_ms = Empty,
+            error.type = Empty,
+            neo4j.error.retryable = Empty
         ),
         err
     )]
-    pub async fn execute(&mut self, query: Query) -> Result<RowStream, neo4rs::Error> {
+    async fn execute_inner(&mut self, query: Query) -> Result<RowStream, neo4rs::Error> {
         debug!("Executing query in transaction");
+        self.config.notify_if_dropped("txn_execute", &self.database);
+        self.lifecycle_event("txn.query", "txn_execute");
+        self.completion.query_count += 1;
+        remember_span(&mut self.query_spans, &Span::current());
 
         // Start timing if metrics are enabled
+        #[cfg(feature = "metrics")]
         let timer = self.metrics.as_ref().map(|_| OperationTimer::start());
 
-        let result = self.inner.execute(query).await;
+        let (result, duration_ms) = self
+            .config
+            .timed("txn_execute", &self.database, self.inner.execute(query))
+            .await;
 
         // Record metrics if enabled
+        #[cfg(feature = "metrics")]
         if let Some(metrics) = &self.metrics {
             if let Some(timer) = timer {
-                let _ = timer.record_query(metrics, result.is_ok(), Some("txn_execute"), "default");
+                let _ = timer.record_result(metrics, &result, Some("txn_execute"), &self.database);
             }
         }
 
         match result {
             Ok(stream) => {
-                info!("Query executed successfully in transaction");
+                log_success!(
+                    self.config,
+                    db.operation.name = "txn_execute",
+                    db.namespace = self.database.as_str(),
+                    duration_ms,
+                    "Query executed successfully in transaction"
+                );
                 Ok(stream)
             }
             Err(e) => {
-                error!("Query execution failed in transaction: {}", e);
+                error!(
+                    db.operation.name = "txn_execute",
+                    db.namespace = self.database.as_str(),
+                    duration_ms,
+                    error.type = classify_error(&e),
+                    "Query execution failed in transaction: {}",
+                    e
+                );
                 Err(e)
             }
         }
@@ -82,40 +239,81 @@ impl InstrumentedTxn {
     /// # Errors
     ///
     /// Returns an error if the query execution fails
+    pub async fn run(&mut self, query: Query) -> Result<(), neo4rs::Error> {
+        traced(self.config.traces("txn_run"), self.run_inner(query)).await
+    }
+
     #[instrument(
+        name = "run",
         skip(self, query),
+        parent = self.parent_span(),
         fields(
+            otel.name = %self.config.span_name("run"),
             otel.kind = "CLIENT",
             db.system.name = "neo4j",
+            db.neo4j.tx.type = "explicit",
+            db.neo4j.business_operation = self.config.business_operation.as_deref(),
+            peer.service = self.config.peer_service.as_deref(),
+            db.system.version = self.server_version.as_deref(),
             server.address = %self.server_address,
             server.port = %self.server_port,
-            db.namespace = "default",
-            db.operation.name = "txn_run"
+            network.transport.encrypted = self.tls_enabled,
+            network.peer.address = self.peer_address.as_deref(),
+            network.peer.port = self.peer_port(),
+            db.namespace = self.database.as_str(),
+            db.operation.name = "txn_run",
+            db.neo4j.op.start_unix_nanos = Empty,
+            db.neo4j.op.end_unix_nanos = Empty,
+            db.neo4j.duration_ms = Empty,
+            error.type = Empty,
+            neo4j.error.retryable = Empty
         ),
         err
     )]
-    pub async fn run(&mut self, query: Query) -> Result<(), neo4rs::Error> {
+    async fn run_inner(&mut self, query: Query) -> Result<(), neo4rs::Error> {
         debug!("Running query in transaction");
+        self.config.notify_if_dropped("txn_run", &self.database);
+        self.lifecycle_event("txn.query", "txn_run");
+        self.completion.query_count += 1;
+        remember_span(&mut self.query_spans, &Span::current());
 
         // Start timing if metrics are enabled
+        #[cfg(feature = "metrics")]
         let timer = self.metrics.as_ref().map(|_| OperationTimer::start());
 
-        let result = self.inner.run(query).await;
+        let (result, duration_ms) = self
+            .config
+            .timed("txn_run", &self.database, self.inner.run(query))
+            .await;
 
         // Record metrics if enabled
+        #[cfg(feature = "metrics")]
         if let Some(metrics) = &self.metrics {
             if let Some(timer) = timer {
-                let _ = timer.record_query(metrics, result.is_ok(), Some("txn_run"), "default");
+                let _ = timer.record_result(metrics, &result, Some("txn_run"), &self.database);
             }
         }
 
         match result {
             Ok(()) => {
-                info!("Query run successfully in transaction");
+                log_success!(
+                    self.config,
+                    db.operation.name = "txn_run",
+                    db.namespace = self.database.as_str(),
+                    duration_ms,
+                    "Query run successfully in transaction"
+                );
                 Ok(())
             }
             Err(e) => {
-                error!("Query run failed in transaction: {}", e);
+                error!(
+                    db.operation.name = "txn_run",
+                    db.namespace = self.database.as_str(),
+                    duration_ms,
+                    error.type = classify_error(&e),
+                    "Query run failed in transaction: {}",
+                    e
+                );
                 Err(e)
             }
         }
@@ -123,45 +321,107 @@ impl InstrumentedTxn {
 
     /// Execute multiple queries sequentially within the transaction
     ///
+    /// With [`with_batch_child_spans`](crate::InstrumentedGraph::with_batch_child_spans)
+    /// enabled each query also gets its own `neo4j.txn_run_queries.item` span.
+    ///
     /// # Errors
     ///
     /// Returns an error if any query execution fails
+    pub async fn run_queries(&mut self, queries: Vec<Query>) -> Result<(), neo4rs::Error> {
+        traced(
+            self.config.traces("txn_run_queries"),
+            self.run_queries_inner(queries),
+        )
+        .await
+    }
+
     #[instrument(
+        name = "run_queries",
         skip(self, queries),
+        parent = self.parent_span(),
         fields(
+            otel.name = %self.config.span_name("run_queries"),
             otel.kind = "CLIENT",
             db.system.name = "neo4j",
+            db.neo4j.tx.type = "explicit",
+            db.neo4j.business_operation = self.config.business_operation.as_deref(),
+            peer.service = self.config.peer_service.as_deref(),
+            db.system.version = self.server_version.as_deref(),
             server.address = %self.server_address,
             server.port = %self.server_port,
-            db.namespace = "default",
+            network.transport.encrypted = self.tls_enabled,
+            network.peer.address = self.peer_address.as_deref(),
+            network.peer.port = self.peer_port(),
+            db.namespace = self.database.as_str(),
             db.operation.name = "txn_run_queries",
+            db.neo4j.op.start_unix_nanos = Empty,
+            db.neo4j.op.end_unix_nanos = Empty,
+            db.neo4j.duration_ms = Empty,
+            error.type = Empty,
+            neo4j.error.retryable = Empty,
             db.operation.batch.size = queries.len()
         ),
         err
     )]
-    pub async fn run_queries(&mut self, queries: Vec<Query>) -> Result<(), neo4rs::Error> {
+    async fn run_queries_inner(&mut self, queries: Vec<Query>) -> Result<(), neo4rs::Error> {
         debug!("Running {} queries in transaction", queries.len());
+        self.config
+            .notify_if_dropped("txn_run_queries", &self.database);
+        self.lifecycle_event("txn.query", "txn_run_queries");
+        self.completion.query_count += queries.len() as u64;
+        remember_span(&mut self.query_spans, &Span::current());
 
         // Start timing if metrics are enabled
+        #[cfg(feature = "metrics")]
         let timer = self.metrics.as_ref().map(|_| OperationTimer::start());
 
-        let result = self.inner.run_queries(queries).await;
+        let (result, duration_ms) = if self.config.batch_child_spans {
+            self.config
+                .timed(
+                    "txn_run_queries",
+                    &self.database,
+                    run_batch_items(&mut self.inner, queries, &self.config),
+                )
+                .await
+        } else {
+            self.config
+                .timed(
+                    "txn_run_queries",
+                    &self.database,
+                    self.inner.run_queries(queries),
+                )
+                .await
+        };
 
         // Record metrics if enabled
+        #[cfg(feature = "metrics")]
         if let Some(metrics) = &self.metrics {
             if let Some(timer) = timer {
                 let _ =
-                    timer.record_query(metrics, result.is_ok(), Some("txn_run_queries"), "default");
+                    timer.record_result(metrics, &result, Some("txn_run_queries"), &self.database);
             }
         }
 
         match result {
             Ok(()) => {
-                info!("Batch queries run successfully in transaction");
+                log_success!(
+                    self.config,
+                    db.operation.name = "txn_run_queries",
+                    db.namespace = self.database.as_str(),
+                    duration_ms,
+                    "Batch queries run successfully in transaction"
+                );
                 Ok(())
             }
             Err(e) => {
-                error!("Batch queries failed in transaction: {}", e);
+                error!(
+                    db.operation.name = "txn_run_queries",
+                    db.namespace = self.database.as_str(),
+                    duration_ms,
+                    error.type = classify_error(&e),
+                    "Batch queries failed in transaction: {}",
+                    e
+                );
                 Err(e)
             }
         }
@@ -172,38 +432,86 @@ impl InstrumentedTxn {
     /// # Errors
     ///
     /// Returns an error if the transaction cannot be committed
+    pub async fn commit(self) -> Result<(), neo4rs::Error> {
+        traced(self.config.tracing_enabled(), self.commit_inner()).await
+    }
+
     #[instrument(
+        name = "commit",
         skip(self),
+        parent = self.parent_span(),
         fields(
+            otel.name = %self.config.span_name("commit"),
             otel.kind = "CLIENT",
             db.system.name = "neo4j",
+            db.neo4j.tx.type = "explicit",
+            db.neo4j.business_operation = self.config.business_operation.as_deref(),
+            peer.service = self.config.peer_service.as_deref(),
+            db.system.version = self.server_version.as_deref(),
             server.address = %self.server_address,
             server.port = %self.server_port,
-            db.namespace = "default",
-            db.operation.name = "txn_commit"
+            network.transport.encrypted = self.tls_enabled,
+            network.peer.address = self.peer_address.as_deref(),
+            network.peer.port = self.peer_port(),
+            db.namespace = self.database.as_str(),
+            db.operation.name = "txn_commit",
+            neo4j.transaction.query_count = self.recorded_query_count(),
+            db.neo4j.op.start_unix_nanos = Empty,
+            db.neo4j.op.end_unix_nanos = Empty,
+            db.neo4j.duration_ms = Empty,
+            error.type = Empty,
+            neo4j.error.retryable = Empty
         ),
         err
     )]
-    pub async fn commit(self) -> Result<(), neo4rs::Error> {
+    async fn commit_inner(mut self) -> Result<(), neo4rs::Error> {
         debug!("Committing transaction");
+        self.completion.complete();
+        self.config.notify_if_dropped("txn_commit", &self.database);
+        self.lifecycle_event("txn.commit", "txn_commit");
+        for query_span in std::mem::take(&mut self.query_spans) {
+            Span::current().add_link(query_span);
+        }
 
-        let result = self.inner.commit().await;
+        let (result, duration_ms) = self
+            .config
+            .timed("txn_commit", &self.database, self.inner.commit())
+            .await;
 
         // Record transaction end if metrics enabled
+        #[cfg(feature = "metrics")]
         if let Some(metrics) = &self.metrics {
-            if let Some(timer) = self.transaction_timer {
+            if let Some(timer) = self.completion.transaction_timer.take() {
                 let duration = timer.elapsed();
-                metrics.record_transaction_end(duration, result.is_ok(), "default");
+                metrics.record_transaction_end(duration, result.is_ok(), &self.database);
             }
+            metrics.record_transaction_queries(
+                self.completion.query_count,
+                result.is_ok(),
+                &self.database,
+            );
         }
 
         match result {
             Ok(()) => {
-                info!("Transaction committed successfully");
+                log_success!(
+                    self.config,
+                    db.operation.name = "txn_commit",
+                    db.namespace = self.database.as_str(),
+                    duration_ms,
+                    "Transaction committed successfully"
+                );
                 Ok(())
             }
             Err(e) => {
-                error!("Transaction commit failed: {}", e);
+                error!(
+                    db.operation.name = "txn_commit",
+                    db.namespace = self.database.as_str(),
+                    duration_ms,
+                    error.type = classify_error(&e),
+                    "Transaction commit failed: {}",
+                    e
+                );
                 Err(e)
             }
         }
@@ -214,43 +522,91 @@ impl InstrumentedTxn {
     /// # Errors
     ///
     /// Returns an error if the transaction cannot be rolled back
+    pub async fn rollback(self) -> Result<(), neo4rs::Error> {
+        traced(self.config.tracing_enabled(), self.rollback_inner()).await
+    }
+
     #[instrument(
+        name = "rollback",
         skip(self),
+        parent = self.parent_span(),
         fields(
+            otel.name = %self.config.span_name("rollback"),
             otel.kind = "CLIENT",
             db.system.name = "neo4j",
+            db.neo4j.tx.type = "explicit",
+            db.neo4j.business_operation = self.config.business_operation.as_deref(),
+            peer.service = self.config.peer_service.as_deref(),
+            db.system.version = self.server_version.as_deref(),
             server.address = %self.server_address,
             server.port = %self.server_port,
-            db.namespace = "default",
-            db.operation.name = "txn_rollback"
+            network.transport.encrypted = self.tls_enabled,
+            network.peer.address = self.peer_address.as_deref(),
+            network.peer.port = self.peer_port(),
+            db.namespace = self.database.as_str(),
+            db.operation.name = "txn_rollback",
+            neo4j.transaction.query_count = self.recorded_query_count(),
+            db.neo4j.op.start_unix_nanos = Empty,
+            db.neo4j.op.end_unix_nanos = Empty,
+            db.neo4j.duration_ms = Empty,
+            error.type = Empty,
+            neo4j.error.retryable = Empty
         ),
         err
     )]
-    pub async fn rollback(self) -> Result<(), neo4rs::Error> {
+    async fn rollback_inner(mut self) -> Result<(), neo4rs::Error> {
         debug!("Rolling back transaction");
+        self.completion.complete();
+        self.config
+            .notify_if_dropped("txn_rollback", &self.database);
+        self.lifecycle_event("txn.rollback", "txn_rollback");
 
-        let result = self.inner.rollback().await;
+        let (result, duration_ms) = self
+            .config
+            .timed("txn_rollback", &self.database, self.inner.rollback())
+            .await;
 
         // Record transaction end if metrics enabled (rollback = not committed)
+        #[cfg(feature = "metrics")]
         if let Some(metrics) = &self.metrics {
-            if let Some(timer) = self.transaction_timer {
+            if let Some(timer) = self.completion.transaction_timer.take() {
                 let duration = timer.elapsed();
-                metrics.record_transaction_end(duration, false, "default");
+                metrics.record_transaction_end(duration, false, &self.database);
             }
+            metrics.record_transaction_queries(self.completion.query_count, false, &self.database);
         }
 
         match result {
             Ok(()) => {
-                info!("Transaction rolled back successfully");
+                log_success!(
+                    self.config,
+                    db.operation.name = "txn_rollback",
+                    db.namespace = self.database.as_str(),
+                    duration_ms,
+                    "Transaction rolled back successfully"
+                );
                 Ok(())
             }
             Err(e) => {
-                error!("Transaction rollback failed: {}", e);
+                error!(
+                    db.operation.name = "txn_rollback",
+                    db.namespace = self.database.as_str(),
+                    duration_ms,
+                    error.type = classify_error(&e),
+                    "Transaction rollback failed: {}",
+                    e
+                );
                 Err(e)
             }
         }
     }
 
+    /// The database this transaction runs against, as recorded in `db.namespace`
+    #[must_use]
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
     /// Get a reference to the underlying transaction
     #[must_use]
     pub fn inner(&self) -> &Txn {
@@ -263,3 +619,193 @@ impl InstrumentedTxn {
         &mut self.inner
     }
 }
+
+/// Reports a transaction that is dropped before `commit` or `rollback` was called
+///
+/// Kept separate from [`InstrumentedTxn`] so that `commit` and `rollback` can still move the
+/// neo4rs transaction out of the wrapper.
+struct Completion {
+    completed: bool,
+    database: String,
+    transaction_span: Option<Span>,
+    tracing_enabled: bool,
+    query_count: u64,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Neo4jMetrics>>,
+    #[cfg(feature = "metrics")]
+    transaction_timer: Option<OperationTimer>,
+}
+
+impl Completion {
+    /// Mark the transaction as explicitly committed or rolled back
+    fn complete(&mut self) {
+        self.completed = true;
+    }
+}
+
+impl Drop for Completion {
+    fn drop(&mut self) {
+        if self.completed {
+            return;
+        }
+
+        if self.tracing_enabled {
+            let parent = self
+                .transaction_span
+                .as_ref()
+                .map_or_else(|| Span::current().id(), Span::id);
+            warn!(
+                parent: parent,
+                db.namespace = self.database.as_str(),
+                "txn.dropped_uncommitted"
+            );
+        }
+
+        #[cfg(feature = "metrics")]
+        if let (Some(metrics), Some(timer)) = (&self.metrics, self.transaction_timer.take()) {
+            metrics.record_transaction_dropped(timer.elapsed(), &self.database);
+            metrics.record_transaction_queries_dropped(self.query_count, &self.database);
+        }
+    }
+}
+
+/// Add the OpenTelemetry context of `span` to `links`, up to [`MAX_QUERY_LINKS`] of them
+///
+/// Spans that are disabled or not recorded have no valid context and are skipped.
+fn remember_span(links: &mut Vec<SpanContext>, span: &Span) {
+    let span_context = span.context().span().span_context().clone();
+    if span_context.is_valid() && links.len() < MAX_QUERY_LINKS {
+        links.push(span_context);
+    }
+}
+
+/// Run `queries` one by one, each inside its own `neo4j.txn_run_queries.item` span
+///
+/// Stops at the first failure like `Txn::run_queries`, leaving the remaining queries unrun.
+pub(crate) async fn run_batch_items(
+    inner: &mut Txn,
+    queries: Vec<Query>,
+    config: &TelemetryConfig,
+) -> Result<(), neo4rs::Error> {
+    for (index, query) in queries.into_iter().enumerate() {
+        let span = tracing::info_span!(
+            "neo4j.txn_run_queries.item",
+            otel.name = %config.span_name("txn_run_queries.item"),
+            db.operation.batch.index = i64::try_from(index).unwrap_or(i64::MAX),
+            error.type = Empty
+        );
+        if let Err(e) = inner.run(query).instrument(span.clone()).await {
+            span.record("error.type", classify_error(&e));
+            record_exception(&span, &e);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::CapturedSpans;
+    #[cfg(feature = "metrics")]
+    use crate::test_support::{histogram_points, sum_points, CapturedMetrics};
+    #[cfg(feature = "metrics")]
+    use opentelemetry::KeyValue;
+
+    fn completion(transaction_span: Option<Span>) -> Completion {
+        Completion {
+            completed: false,
+            database: "orders".to_string(),
+            transaction_span,
+            tracing_enabled: true,
+            query_count: 0,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            #[cfg(feature = "metrics")]
+            transaction_timer: None,
+        }
+    }
+
+    fn dropped_events(capture: &CapturedSpans) -> usize {
+        capture
+            .span("neo4j.transaction")
+            .events
+            .iter()
+            .filter(|event| event.name == "txn.dropped_uncommitted")
+            .count()
+    }
+
+    #[test]
+    fn test_drop_without_completion_warns() {
+        let capture = CapturedSpans::install();
+        let span = tracing::info_span!("neo4j.transaction");
+
+        drop(completion(Some(span.clone())));
+        drop(span);
+
+        assert_eq!(dropped_events(&capture), 1);
+    }
+
+    #[test]
+    fn test_completed_transaction_drops_quietly() {
+        let capture = CapturedSpans::install();
+        let span = tracing::info_span!("neo4j.transaction");
+
+        let mut completion = completion(Some(span.clone()));
+        completion.complete();
+        drop(completion);
+        drop(span);
+
+        assert_eq!(dropped_events(&capture), 0);
+    }
+
+    #[test]
+    fn test_commit_span_links_remembered_queries() {
+        let capture = CapturedSpans::install();
+        let mut links = Vec::new();
+
+        let query = tracing::info_span!("query");
+        remember_span(&mut links, &query);
+        let query_id = query.context().span().span_context().span_id();
+        drop(query);
+        remember_span(&mut links, &Span::none());
+        assert_eq!(links.len(), 1);
+
+        let commit = tracing::info_span!("commit");
+        for link in links {
+            commit.add_link(link);
+        }
+        drop(commit);
+
+        let commit = capture.span("commit");
+        let linked: Vec<_> = commit
+            .links
+            .iter()
+            .map(|link| link.span_context.span_id())
+            .collect();
+        assert_eq!(linked, [query_id]);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_drop_records_dropped_rollback() {
+        let captured = CapturedMetrics::install();
+        let mut completion = completion(None);
+        completion.metrics = Some(captured.metrics.clone());
+        completion.transaction_timer = Some(OperationTimer::start());
+        completion.query_count = 3;
+
+        drop(completion);
+
+        let points = sum_points(&captured, "neo4j.transaction.rollbacks");
+        let (attributes, value) = &points[0];
+        assert_eq!(*value, 1);
+        assert!(attributes.contains(&KeyValue::new("outcome", "dropped")));
+        let points = histogram_points::<u64>(&captured, "neo4j.transaction.queries");
+        assert_eq!(points[0].sum, 3);
+        assert_eq!(
+            points[0].attribute("outcome"),
+            Some(&opentelemetry::Value::from("dropped"))
+        );
+    }
+}
@@ -1,69 +1,15 @@
 #![cfg(all(test, feature = "integration"))]
 
+use futures::TryStreamExt;
 use neo4rs::Query;
-use opentelemetry::{
-    global,
-    trace::{SpanKind, Status, TraceContextExt, Tracer, TracerProvider as OtelTracerProvider},
-};
-use opentelemetry_sdk::trace::{
-    InMemorySpanExporter, SdkTracerProvider as TracerProvider, SpanData,
-};
+use opentelemetry::trace::{Span as _, SpanKind, Status, TraceContextExt, Tracer};
+use opentelemetry_sdk::trace::SpanData;
 use opentelemetry_semantic_conventions::attribute::{
     DB_NAMESPACE, DB_OPERATION_NAME, DB_QUERY_TEXT, DB_SYSTEM_NAME, SERVER_ADDRESS,
 };
+use otel_instrumentation_neo4jrs::query::TracedQuery;
+use otel_instrumentation_neo4jrs::testing::SpanCapture;
 use otel_instrumentation_neo4jrs::InstrumentedGraph;
-use tracing_subscriber::prelude::*;
-
-struct TestHarness {
-    provider: TracerProvider,
-    exporter: InMemorySpanExporter,
-    _guard: tracing::subscriber::DefaultGuard,
-}
-
-impl TestHarness {
-    fn new() -> Self {
-        // Create InMemorySpanExporter for testing
-        let exporter = InMemorySpanExporter::default();
-
-        // Build provider with simple exporter
-        let provider = TracerProvider::builder()
-            .with_simple_exporter(exporter.clone())
-            .build();
-
-        // Create tracer from provider
-        let tracer = provider.tracer("test");
-
-        // Set as global provider for any code that uses global::tracer
-        global::set_tracer_provider(provider.clone());
-
-        // Set up tracing subscriber with OpenTelemetry layer
-        // Use set_default to scope it to this test thread
-        let telemetry = tracing_opentelemetry::layer().with_tracer(tracer);
-        let subscriber = tracing_subscriber::registry().with(telemetry);
-        let guard = tracing::subscriber::set_default(subscriber);
-
-        Self {
-            provider,
-            exporter,
-            _guard: guard,
-        }
-    }
-
-    fn get_spans(&self) -> Vec<SpanData> {
-        // Force flush of any pending spans
-        let _ = self.provider.force_flush();
-        self.exporter.get_finished_spans().unwrap()
-    }
-
-    fn reset(&self) {
-        self.exporter.reset();
-    }
-
-    fn tracer(&self, name: &'static str) -> opentelemetry::global::BoxedTracer {
-        // Use global tracer which returns BoxedTracer
-        global::tracer(name)
-    }
-}
 
 fn get_neo4j_connection_string() -> String {
     std::env::var("NEO4J_TEST_URI").unwrap_or_else(|_| "bolt://localhost:7687".to_string())
@@ -78,7 +24,7 @@ fn get_neo4j_password() -> String {
 }
 
 async fn setup_test_graph(
-    harness: &TestHarness,
+    capture: &SpanCapture,
 ) -> Result<InstrumentedGraph, Box<dyn std::error::Error>> {
     let uri = get_neo4j_connection_string();
     let user = get_neo4j_user();
@@ -93,7 +39,7 @@ async fn setup_test_graph(
         .await?;
 
     // Clear any spans from the cleanup operation
-    harness.reset();
+    capture.reset();
 
     Ok(graph)
 }
@@ -146,8 +92,8 @@ fn validate_db_span_attributes(span: &SpanData) {
 
 #[tokio::test]
 async fn test_instrumented_run_query() -> Result<(), Box<dyn std::error::Error>> {
-    let harness = TestHarness::new();
-    let graph = setup_test_graph(&harness).await?;
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
 
     // Execute a simple query
     let query =
@@ -156,17 +102,17 @@ async fn test_instrumented_run_query() -> Result<(), Box<dyn std::error::Error>>
     graph.run(query).await?;
 
     // Get the exported spans
-    let spans = harness.get_spans();
+    let spans = capture.spans();
 
     assert!(
         !spans.is_empty(),
         "Expected at least one span to be created"
     );
 
-    // Find the run span (our implementation uses neo4j.run, not neo4j.query)
+    // Exported span names are `<prefix>.<operation>`, with the default `neo4j` prefix
     let query_span = spans
         .iter()
-        .find(|s| s.name == "neo4j.run" || s.name.contains("run"))
+        .find(|s| s.name == "neo4j.run")
         .expect("Should have a run span");
 
     // Validate span attributes
@@ -192,8 +138,8 @@ async fn test_instrumented_run_query() -> Result<(), Box<dyn std::error::Error>>
 
 #[tokio::test]
 async fn test_instrumented_execute_query() -> Result<(), Box<dyn std::error::Error>> {
-    let harness = TestHarness::new();
-    let graph = setup_test_graph(&harness).await?;
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
 
     // Execute a query and fetch results
     let query =
@@ -204,7 +150,7 @@ async fn test_instrumented_execute_query() -> Result<(), Box<dyn std::error::Err
     graph.run(query).await?;
 
     // Check that spans were created
-    let spans = harness.get_spans();
+    let spans = capture.spans();
     assert!(
         !spans.is_empty(),
         "Expected at least one span to be created"
@@ -212,7 +158,7 @@ async fn test_instrumented_execute_query() -> Result<(), Box<dyn std::error::Err
 
     // Validate all database spans
     for span in spans.iter() {
-        if span.name.contains("neo4j") || span.name.contains("execute") {
+        if span.name == "neo4j.run" {
             validate_db_span_attributes(span);
         }
     }
@@ -222,8 +168,8 @@ async fn test_instrumented_execute_query() -> Result<(), Box<dyn std::error::Err
 
 #[tokio::test]
 async fn test_instrumented_transaction() -> Result<(), Box<dyn std::error::Error>> {
-    let harness = TestHarness::new();
-    let graph = setup_test_graph(&harness).await?;
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
 
     // Start a transaction
     let mut txn = graph.start_txn().await?;
@@ -236,27 +182,32 @@ async fn test_instrumented_transaction() -> Result<(), Box<dyn std::error::Error
     txn.commit().await?;
 
     // Check that spans were created
-    let spans = harness.get_spans();
+    let spans = capture.spans();
     assert!(
         !spans.is_empty(),
         "Expected transaction spans to be created"
     );
 
     // Look for transaction-related spans
-    let has_tx_start = spans.iter().any(|s| {
-        s.name == "neo4j.transaction"
-            || s.name == "neo4j.transaction.start"
-            || s.name.contains("start_txn")
-    });
-    let has_tx_commit = spans
-        .iter()
-        .any(|s| s.name == "neo4j.transaction.commit" || s.name.contains("commit"));
+    let transaction_spans = ["neo4j.start_txn", "neo4j.run", "neo4j.commit"];
+    for name in transaction_spans {
+        assert!(
+            spans.iter().any(|s| s.name == name),
+            "Expected a {name} span"
+        );
+    }
 
-    assert!(has_tx_start || has_tx_commit, "Expected transaction spans");
+    // The commit span links to the query it committed
+    let run = spans.iter().find(|s| s.name == "neo4j.run").unwrap();
+    let commit = spans.iter().find(|s| s.name == "neo4j.commit").unwrap();
+    assert!(commit
+        .links
+        .iter()
+        .any(|link| link.span_context.span_id() == run.span_context.span_id()));
 
     // Validate all spans
     for span in spans.iter() {
-        if span.name.contains("neo4j") || span.name.contains("transaction") {
+        if transaction_spans.contains(&span.name.as_ref()) {
             validate_db_span_attributes(span);
         }
     }
@@ -266,8 +217,8 @@ async fn test_instrumented_transaction() -> Result<(), Box<dyn std::error::Error
 
 #[tokio::test]
 async fn test_query_with_parameters() -> Result<(), Box<dyn std::error::Error>> {
-    let harness = TestHarness::new();
-    let graph = setup_test_graph(&harness).await?;
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
 
     // Create a query with multiple parameters
     let query = Query::new(
@@ -280,7 +231,7 @@ async fn test_query_with_parameters() -> Result<(), Box<dyn std::error::Error>>
     graph.run(query).await?;
 
     // Verify spans contain query information
-    let spans = harness.get_spans();
+    let spans = capture.spans();
 
     println!("Parameters test - Total spans: {}", spans.len());
     for span in &spans {
@@ -297,7 +248,7 @@ async fn test_query_with_parameters() -> Result<(), Box<dyn std::error::Error>>
 
     let query_span = spans
         .iter()
-        .find(|s| s.name.contains("query") || s.name.contains("neo4j") || s.name.contains("run"))
+        .find(|s| s.name == "neo4j.run")
         .expect("Should have a query span");
 
     validate_db_span_attributes(query_span);
@@ -317,8 +268,8 @@ async fn test_query_with_parameters() -> Result<(), Box<dyn std::error::Error>>
 
 #[tokio::test]
 async fn test_error_handling() -> Result<(), Box<dyn std::error::Error>> {
-    let harness = TestHarness::new();
-    let graph = setup_test_graph(&harness).await?;
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
 
     // Execute an invalid query that should fail
     let invalid_query = Query::new("INVALID CYPHER SYNTAX".to_string());
@@ -327,21 +278,23 @@ async fn test_error_handling() -> Result<(), Box<dyn std::error::Error>> {
     assert!(result.is_err(), "Expected query to fail");
 
     // Check that error spans were created
-    let spans = harness.get_spans();
+    let spans = capture.spans();
     assert!(!spans.is_empty());
 
     // Verify the span indicates an error
-    let error_span = spans.iter().find(|span| span.status == Status::error(""));
-
-    if let Some(span) = error_span {
-        // Check for error attributes
-        let has_error_info = span.attributes.iter().any(|kv| {
-            let key = kv.key.as_str();
-            key.starts_with("error.") || key == "exception.type" || key == "exception.message"
-        });
+    let error_span = spans
+        .iter()
+        .find(|span| matches!(span.status, Status::Error { .. }))
+        .expect("Should have an error span");
+
+    // Check for the exception semantic convention attributes
+    for key in ["error.type", "exception.type", "exception.message"] {
         assert!(
-            has_error_info || span.status == Status::error(""),
-            "Error spans should have error information"
+            error_span
+                .attributes
+                .iter()
+                .any(|kv| kv.key.as_str() == key),
+            "Error span should have {key}"
         );
     }
 
@@ -350,11 +303,11 @@ async fn test_error_handling() -> Result<(), Box<dyn std::error::Error>> {
 
 #[tokio::test]
 async fn test_span_relationships() -> Result<(), Box<dyn std::error::Error>> {
-    let harness = TestHarness::new();
-    let graph = setup_test_graph(&harness).await?;
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
 
     // Create a tracer for parent span
-    let tracer = harness.tracer("test");
+    let tracer = capture.tracer("test");
     let parent_span = tracer.start("parent_operation");
     let cx = opentelemetry::Context::current().with_span(parent_span);
 
@@ -366,7 +319,7 @@ async fn test_span_relationships() -> Result<(), Box<dyn std::error::Error>> {
     drop(_guard); // Explicitly drop the guard to end the parent span
 
     // Get spans and check parent-child relationships
-    let spans = harness.get_spans();
+    let spans = capture.spans();
     assert!(spans.len() >= 2, "Expected parent and child spans");
 
     // Find parent span
@@ -385,3 +338,611 @@ async fn test_span_relationships() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_run_on_records_same_attributes_as_run() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+
+    graph
+        .run(Query::new("CREATE (n:TestNode {via: 'run'})".to_string()))
+        .await?;
+    graph
+        .run_on(
+            "neo4j",
+            Query::new("CREATE (n:TestNode {via: 'run_on'})".to_string()),
+        )
+        .await?;
+
+    let spans = capture.spans();
+    let attribute_keys = |name: &str| {
+        let span = spans
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap_or_else(|| panic!("Should have a {name} span"));
+        let mut keys: Vec<String> = span
+            .attributes
+            .iter()
+            .map(|kv| kv.key.as_str().to_string())
+            .collect();
+        keys.sort();
+        keys
+    };
+
+    assert_eq!(attribute_keys("neo4j.run"), attribute_keys("neo4j.run_on"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_scalar_counts_rows() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+
+    graph
+        .run(Query::new(
+            "UNWIND range(1, 5) AS id CREATE (n:TestNode {id: id})".to_string(),
+        ))
+        .await?;
+    capture.reset();
+
+    let ids: Vec<i64> = graph
+        .stream_scalar::<i64>(
+            Query::new("MATCH (n:TestNode) RETURN n.id AS id ORDER BY id".to_string()),
+            "id",
+        )
+        .await?
+        .try_collect()
+        .await?;
+
+    assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+
+    let spans = capture.spans();
+    let span = spans
+        .iter()
+        .find(|s| s.name == "neo4j.stream_scalar")
+        .expect("Should have a stream_scalar span");
+    let returned_rows = span
+        .attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == "db.response.returned_rows")
+        .map(|kv| kv.value.clone());
+    assert_eq!(returned_rows, Some(opentelemetry::Value::I64(5)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_scalar_drains_stream() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+
+    graph
+        .run(Query::new(
+            "UNWIND range(1, 3) AS id CREATE (n:TestNode {id: id})".to_string(),
+        ))
+        .await?;
+    capture.reset();
+
+    let first: Option<i64> = graph
+        .execute_scalar(
+            Query::new("MATCH (n:TestNode) RETURN n.id AS id ORDER BY id".to_string()),
+            "id",
+        )
+        .await?;
+    assert_eq!(first, Some(1));
+
+    let missing: Option<i64> = graph
+        .execute_scalar(
+            Query::new("MATCH (n:Missing) RETURN n.id AS id".to_string()),
+            "id",
+        )
+        .await?;
+    assert_eq!(missing, None);
+
+    let spans = capture.spans();
+    let returned_rows: Vec<_> = spans
+        .iter()
+        .filter(|s| s.name == "neo4j.execute_scalar")
+        .filter_map(|s| {
+            s.attributes
+                .iter()
+                .find(|kv| kv.key.as_str() == "db.response.returned_rows")
+                .map(|kv| kv.value.clone())
+        })
+        .collect();
+    assert_eq!(
+        returned_rows,
+        vec![opentelemetry::Value::I64(3), opentelemetry::Value::I64(0)]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_query_collect_reads_all_rows() -> Result<(), Box<dyn std::error::Error>> {
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Node {
+        id: i64,
+    }
+
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+
+    graph
+        .run(Query::new(
+            "UNWIND range(1, 3) AS id CREATE (n:TestNode {id: id})".to_string(),
+        ))
+        .await?;
+    capture.reset();
+
+    let nodes: Vec<Node> = graph
+        .query_collect(Query::new(
+            "MATCH (n:TestNode) RETURN n.id AS id ORDER BY id".to_string(),
+        ))
+        .await?;
+    assert_eq!(nodes, vec![Node { id: 1 }, Node { id: 2 }, Node { id: 3 }]);
+
+    let spans = capture.spans();
+    let span = spans
+        .iter()
+        .find(|s| s.name == "neo4j.query_collect")
+        .expect("Should have a query_collect span");
+    let returned_rows = span
+        .attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == "db.response.returned_rows")
+        .map(|kv| kv.value.clone());
+    assert_eq!(returned_rows, Some(opentelemetry::Value::I64(3)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_in_context_parents_to_otel_span() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+    capture.reset();
+
+    // A span managed purely through the OpenTelemetry API, never entered as a tracing span
+    let parent = capture.tracer("app").start("place_order");
+    let parent_context = parent.span_context().clone();
+    let cx = opentelemetry::Context::current_with_span(parent);
+    graph
+        .run_in_context(&cx, Query::new("RETURN 1".to_string()))
+        .await?;
+    cx.span().end();
+
+    let spans = capture.spans();
+    let run = spans
+        .iter()
+        .find(|s| s.name == "neo4j.run")
+        .expect("Should have a run span");
+    assert_eq!(run.span_context.trace_id(), parent_context.trace_id());
+    assert_eq!(run.parent_span_id, parent_context.span_id());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_traced_query_records_text() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+
+    let cypher = "CREATE (n:TestNode {name: $name, tags: $tags})";
+    graph
+        .run_traced_query(
+            TracedQuery::new(cypher)
+                .param("name", "traced")
+                .param("tags", vec!["a", "b", "c"]),
+        )
+        .await?;
+
+    let spans = capture.spans();
+    let span = spans
+        .iter()
+        .find(|s| s.name == "CREATE TestNode")
+        .expect("Traced run span should be named after its operation");
+    let attribute = |key: &str| {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.clone())
+    };
+    assert_eq!(
+        attribute("db.query.text"),
+        Some(opentelemetry::Value::from(cypher))
+    );
+    assert_eq!(
+        attribute("db.operation.name"),
+        Some(opentelemetry::Value::from("CREATE TestNode"))
+    );
+    assert_eq!(
+        attribute("db.query.parameter.tags.size"),
+        Some(opentelemetry::Value::I64(3))
+    );
+
+    // Parameters must reach Neo4j exactly as they would with a plain Query
+    let tags: Vec<i64> = graph
+        .stream_scalar::<i64>(
+            Query::new(
+                "MATCH (n:TestNode {name: 'traced'}) RETURN size(n.tags) AS tags".to_string(),
+            ),
+            "tags",
+        )
+        .await?
+        .try_collect()
+        .await?;
+    assert_eq!(tags, vec![3]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_duration_attribute_matches_span_time() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+
+    graph
+        .run(Query::new("CREATE (n:TestNode {timed: true})".to_string()))
+        .await?;
+    let mut txn = graph.start_txn().await?;
+    txn.run(Query::new("CREATE (n:TestNode {timed: true})".to_string()))
+        .await?;
+    txn.commit().await?;
+
+    let spans = capture.spans();
+    for name in ["run", "start_transaction", "txn_run", "txn_commit"] {
+        let span = spans
+            .iter()
+            .find(|s| {
+                s.attributes
+                    .iter()
+                    .any(|kv| kv.key.as_str() == "db.operation.name" && kv.value.as_str() == name)
+            })
+            .unwrap_or_else(|| panic!("Should have a {name} span"));
+        let duration_ms = span
+            .attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == "db.neo4j.duration_ms")
+            .and_then(|kv| match kv.value {
+                opentelemetry::Value::F64(v) => Some(v),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("{name} span should record db.neo4j.duration_ms"));
+        let wall_ms = span.end_time.duration_since(span.start_time)?.as_secs_f64() * 1000.0;
+
+        assert!(
+            duration_ms <= wall_ms + 1.0,
+            "{name}: duration {duration_ms}ms exceeds span wall time {wall_ms}ms"
+        );
+        assert!(
+            duration_ms >= wall_ms * 0.5 - 5.0,
+            "{name}: duration {duration_ms}ms far below span wall time {wall_ms}ms"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_records_partial_row_count() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+
+    let mut rows = graph
+        .execute(Query::new(
+            "UNWIND range(1, 10) AS id RETURN id".to_string(),
+        ))
+        .await?;
+    rows.next().await?;
+    rows.next().await?;
+    rows.next().await?;
+    drop(rows);
+
+    let spans = capture.spans();
+    let span = spans
+        .iter()
+        .find(|s| s.name == "neo4j.execute")
+        .expect("Should have an execute span");
+    let returned_rows = span
+        .attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == "db.response.returned_rows")
+        .map(|kv| kv.value.clone());
+    assert_eq!(returned_rows, Some(opentelemetry::Value::I64(3)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_server_version_recorded_on_operations() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+    let version = graph
+        .server_version()
+        .expect("connect should detect the server version")
+        .to_string();
+
+    graph.run(Query::new("RETURN 1".to_string())).await?;
+
+    let spans = capture.spans();
+    let span = spans
+        .iter()
+        .find(|s| s.name == "neo4j.run")
+        .expect("Should have a run span");
+    let recorded = span
+        .attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == "db.system.version")
+        .map(|kv| kv.value.to_string());
+    assert_eq!(recorded.as_deref(), Some(version.as_str()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_business_operation_spans_transactions() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+    let checkout = graph.clone().with_business_operation("checkout");
+
+    for step in ["reserve", "order"] {
+        let mut txn = checkout.start_txn().await?;
+        txn.run(Query::new(format!(
+            "CREATE (n:TestNode {{step: '{step}'}})"
+        )))
+        .await?;
+        txn.commit().await?;
+    }
+
+    let spans = capture.spans();
+    let tagged = |operation: &str| {
+        spans
+            .iter()
+            .filter(|s| {
+                s.attributes.iter().any(|kv| {
+                    kv.key.as_str() == "db.operation.name" && kv.value.as_str() == operation
+                })
+            })
+            .filter(|s| {
+                s.attributes.iter().any(|kv| {
+                    kv.key.as_str() == "db.neo4j.business_operation"
+                        && kv.value.as_str() == "checkout"
+                })
+            })
+            .count()
+    };
+
+    assert_eq!(tagged("start_transaction"), 2);
+    assert_eq!(tagged("txn_run"), 2);
+    assert_eq!(tagged("txn_commit"), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_on_many_spans_each_database() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+
+    let results = graph
+        .run_on_many(&["neo4j", "system"], Query::new("RETURN 1".to_string()))
+        .await;
+    assert_eq!(results.len(), 2);
+
+    let spans = capture.spans();
+    let parent = spans
+        .iter()
+        .find(|s| s.name == "neo4j.run_on_many")
+        .expect("Should have a run_on_many span");
+    let database_count = parent
+        .attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == "db.neo4j.database_count")
+        .map(|kv| kv.value.clone());
+    assert_eq!(database_count, Some(opentelemetry::Value::I64(2)));
+
+    let mut namespaces: Vec<String> = spans
+        .iter()
+        .filter(|s| s.parent_span_id == parent.span_context.span_id())
+        .filter_map(|s| {
+            s.attributes
+                .iter()
+                .find(|kv| kv.key.as_str() == DB_NAMESPACE)
+                .map(|kv| kv.value.to_string())
+        })
+        .collect();
+    namespaces.sort();
+    assert_eq!(namespaces, vec!["neo4j", "system"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_with_retry_records_attempts() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+
+    graph
+        .run_with_retry(
+            Query::new("CREATE (n:TestNode {name: 'retry'})".to_string()),
+            otel_instrumentation_neo4jrs::RetryPolicy::new(),
+        )
+        .await?;
+
+    let spans = capture.spans();
+    let parent = spans
+        .iter()
+        .find(|s| s.name == "neo4j.run_with_retry")
+        .expect("Should have a run_with_retry span");
+    let retry_count = parent
+        .attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == "retry.count")
+        .map(|kv| kv.value.clone());
+    assert_eq!(retry_count, Some(opentelemetry::Value::I64(0)));
+
+    let attempts: Vec<&SpanData> = spans
+        .iter()
+        .filter(|s| s.name == "neo4j.run.attempt")
+        .collect();
+    assert_eq!(attempts.len(), 1);
+    assert_eq!(attempts[0].parent_span_id, parent.span_context.span_id());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tx_type_distinguishes_implicit_and_explicit() -> Result<(), Box<dyn std::error::Error>>
+{
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+
+    graph.run(Query::new("RETURN 1".to_string())).await?;
+    let mut txn = graph.start_txn().await?;
+    txn.run(Query::new("RETURN 1".to_string())).await?;
+    txn.commit().await?;
+
+    let spans = capture.spans();
+    let tx_type = |operation: &str| {
+        spans
+            .iter()
+            .find(|s| {
+                s.attributes.iter().any(|kv| {
+                    kv.key.as_str() == "db.operation.name" && kv.value.as_str() == operation
+                })
+            })
+            .and_then(|s| {
+                s.attributes
+                    .iter()
+                    .find(|kv| kv.key.as_str() == "db.neo4j.tx.type")
+                    .map(|kv| kv.value.to_string())
+            })
+    };
+
+    assert_eq!(tx_type("run").as_deref(), Some("implicit"));
+    assert_eq!(tx_type("txn_run").as_deref(), Some("explicit"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_start_txn_on_propagates_database() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture).await?;
+
+    let mut txn = graph.start_txn_on("neo4j").await?;
+    assert_eq!(txn.database(), "neo4j");
+    txn.run(Query::new("RETURN 1".to_string())).await?;
+    txn.commit().await?;
+
+    let spans = capture.spans();
+    for operation in ["start_transaction", "txn_run", "txn_commit"] {
+        let span = spans
+            .iter()
+            .find(|s| {
+                s.attributes.iter().any(|kv| {
+                    kv.key.as_str() == DB_OPERATION_NAME && kv.value.as_str() == operation
+                })
+            })
+            .unwrap_or_else(|| panic!("Should have a {operation} span"));
+        let namespace = span
+            .attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == DB_NAMESPACE)
+            .map(|kv| kv.value.to_string());
+        assert_eq!(namespace.as_deref(), Some("neo4j"), "{operation}");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_batch_child_spans_identify_failing_query() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture)
+        .await?
+        .with_batch_child_spans(true);
+
+    let mut txn = graph.start_txn().await?;
+    let result = txn
+        .run_queries(vec![
+            Query::new("CREATE (n:TestNode {name: 'batch'})".to_string()),
+            Query::new("THIS IS NOT CYPHER".to_string()),
+            Query::new("CREATE (n:TestNode {name: 'unreached'})".to_string()),
+        ])
+        .await;
+    assert!(result.is_err());
+    let _ = txn.rollback().await;
+
+    let spans = capture.spans();
+    let items: Vec<&SpanData> = spans
+        .iter()
+        .filter(|s| s.name == "neo4j.txn_run_queries.item")
+        .collect();
+    assert_eq!(items.len(), 2, "the batch stops at the failing query");
+
+    let failed = items
+        .iter()
+        .find(|s| {
+            s.attributes
+                .iter()
+                .any(|kv| kv.key.as_str() == "error.type")
+        })
+        .expect("Should have a failed item span");
+    let index = failed
+        .attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == "db.operation.batch.index")
+        .map(|kv| kv.value.clone());
+    assert_eq!(index, Some(opentelemetry::Value::I64(1)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transaction_span_groups_lifecycle() -> Result<(), Box<dyn std::error::Error>> {
+    let capture = SpanCapture::install();
+    let graph = setup_test_graph(&capture)
+        .await?
+        .with_transaction_span(true);
+
+    let mut txn = graph.start_txn().await?;
+    txn.run(Query::new(
+        "CREATE (n:TestNode {name: 'lifecycle'})".to_string(),
+    ))
+    .await?;
+    txn.rollback().await?;
+
+    let spans = capture.spans();
+    let transaction = spans
+        .iter()
+        .find(|s| s.name == "neo4j.transaction")
+        .expect("Should have a transaction span");
+    let events: Vec<&str> = transaction.events.iter().map(|e| e.name.as_ref()).collect();
+    assert_eq!(events, vec!["txn.begin", "txn.query", "txn.rollback"]);
+
+    let children: Vec<&str> = spans
+        .iter()
+        .filter(|s| s.parent_span_id == transaction.span_context.span_id())
+        .map(|s| s.name.as_ref())
+        .collect();
+    assert_eq!(
+        children,
+        vec!["neo4j.start_txn", "neo4j.run", "neo4j.rollback"]
+    );
+
+    let rollback = spans
+        .iter()
+        .find(|s| s.name == "neo4j.rollback")
+        .expect("Should have a rollback span");
+    let query_count = rollback
+        .attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == "neo4j.transaction.query_count")
+        .map(|kv| kv.value.clone());
+    assert_eq!(query_count, Some(opentelemetry::Value::I64(1)));
+
+    Ok(())
+}